@@ -1,13 +1,31 @@
-use alphacou::Solver;
+use alphacou::{Alphacou, Solver};
 use ascacou::Board;
 use clap::Parser;
+use std::io;
+use std::process;
 use std::time::Duration;
 
+/// Parse a FEN and check that it describes a possible position.
+fn parse_board(fen: &str) -> Result<Board, String> {
+	let board = Board::from_fen(fen).map_err(|error| error.to_string())?;
+	board.validate().map_err(|violations| {
+		format!(
+			"invalid board: {}",
+			violations
+				.iter()
+				.map(|violation| violation.to_string())
+				.collect::<Vec<String>>()
+				.join(", ")
+		)
+	})?;
+	Ok(board)
+}
+
 #[derive(Parser)]
 #[clap(about, author, version)]
 struct Args {
 	/// Name of the person to greet
-	#[clap(value_parser = Board::from_fen, default_value_t = Board::empty())]
+	#[clap(value_parser = parse_board, default_value_t = Board::empty())]
 	board: Board,
 
 	/// Number of times for the IA to find a move (in s)
@@ -17,10 +35,23 @@ struct Args {
 	/// Print a sequence of moves rather than a single move.
 	#[clap(short, long)]
 	sequence: bool,
+
+	/// Talk the UCI-style engine protocol on standard input and
+	/// output, see `ascacou::Engine`. Other arguments are ignored.
+	#[clap(long)]
+	uci: bool,
 }
 
 fn main() {
 	let args = Args::parse();
+	if args.uci {
+		let input = io::BufReader::new(io::stdin());
+		if let Err(error) = ascacou::serve(&mut Alphacou::default(), input, io::stdout()) {
+			eprintln!("{}", error);
+			process::exit(1);
+		}
+		return;
+	}
 	if args.sequence {
 		println!(
 			"{}",
@@ -34,7 +65,7 @@ fn main() {
 		println!(
 			"{}",
 			Solver::best_move(args.board, Duration::from_secs(args.duration))
-				.and_then(|mov| Some(mov.to_string()))
+				.map(|mov| mov.to_string())
 				.unwrap_or("N.A.".to_string())
 		);
 	}
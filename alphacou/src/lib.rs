@@ -2,6 +2,10 @@ use ascacou::{Board, Color, Move};
 use indextree::{Arena, NodeId};
 use std::time::{Duration, Instant};
 
+mod protocol;
+
+pub use protocol::Alphacou;
+
 #[derive(Debug)]
 struct NodeData {
 	/// Number of times a node or its children has been visited.
@@ -108,15 +112,15 @@ impl Solver {
 				Some(node_id) => id = node_id,
 				None => break,
 			}
-			board = board
-				.next(&self.get_data(id).mov)
+			board
+				.play(self.get_data(id).mov)
 				.expect("should play valid move");
 		}
 
 		(board, id)
 	}
 
-	fn expand(&mut self, board: Board, node_id: NodeId) -> (Board, NodeId) {
+	fn expand(&mut self, mut board: Board, node_id: NodeId) -> (Board, NodeId) {
 		use rand::seq::IteratorRandom;
 		let mut rng = rand::rng();
 
@@ -126,16 +130,13 @@ impl Solver {
 			.collect();
 		if let Some(mov) = board
 			.possible_moves()
-			.into_iter()
 			.filter(|mov| !already_expanded_moves.contains(mov))
 			.choose(&mut rng)
 		{
 			let new_node_id = self.arena.new_node(NodeData::new(mov));
 			node_id.append(new_node_id, &mut self.arena);
-			(
-				board.next(&mov).expect("should play valid moves"),
-				new_node_id,
-			)
+			board.play(mov).expect("should play valid moves");
+			(board, new_node_id)
 		} else {
 			(board, node_id)
 		}
@@ -148,16 +149,16 @@ impl Solver {
 		let mut current_player = 1i8;
 		let mut rng = rand::rng();
 
-		while let Some(mov) = board.possible_moves().into_iter().choose(&mut rng) {
+		while let Some(mov) = board.possible_moves().choose(&mut rng) {
 			current_player = -current_player;
-			board = board.next(&mov).expect("should play valid move");
+			board.play(mov).expect("should play valid move");
 		}
 		(board.current_score() * current_player).clamp(-1, 1) as i32
 	}
 
 	fn backpropagate(&mut self, node_id: NodeId, value: i32) {
 		let mut current_player = 1;
-		for id in node_id.ancestors(&mut self.arena).collect::<Vec<NodeId>>() {
+		for id in node_id.ancestors(&self.arena).collect::<Vec<NodeId>>() {
 			let parent_visits = if let Some(pid) = self.arena.get(id).unwrap().parent() {
 				// We add 1 there because the visit count has not been updated yet.
 				self.arena.get(pid).map(|node| node.get()).unwrap().visits + 1
@@ -167,7 +168,7 @@ impl Solver {
 			let data = self.arena.get_mut(id).map(|node| node.get_mut()).unwrap();
 			data.visits += 1;
 			data.score += value * current_player;
-			data.uct = Solver::compute_uct(&data, parent_visits);
+			data.uct = Solver::compute_uct(data, parent_visits);
 			current_player = -current_player;
 		}
 	}
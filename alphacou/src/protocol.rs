@@ -0,0 +1,136 @@
+use crate::Solver;
+use ascacou::{Board, EngineOption, Info, Move, Score, Search};
+use std::time::Duration;
+
+/// Iterations run between two checks of the search limits.
+const CHECK_INTERVAL: usize = 64;
+
+/// The Monte Carlo tree search, running until a limit of `go` is
+/// reached. Nodes are search iterations, and the depth is the length
+/// of the principal variation.
+pub struct Alphacou {
+	/// Search time when `go` is given no limit.
+	pub movetime: Duration,
+}
+
+impl Default for Alphacou {
+	fn default() -> Alphacou {
+		Alphacou {
+			movetime: Duration::from_secs(1),
+		}
+	}
+}
+
+impl Alphacou {
+	fn info(solver: &Solver, search: &Search) -> Info {
+		let pv = solver.current_best_continuation();
+		let score = solver.best_next_node(solver.root).map(|node_id| {
+			let data = solver.get_data(node_id);
+			// Simulations are scored for the player to move after the
+			// move of the node.
+			Score::Mean(-data.score as f64 / data.visits as f64)
+		});
+		Info {
+			depth: pv.len() as u8,
+			score,
+			nodes: solver.iterations as u64,
+			time: search.elapsed(),
+			pv,
+		}
+	}
+}
+
+impl ascacou::Engine for Alphacou {
+	fn name(&self) -> String {
+		format!("alphacou {}", env!("CARGO_PKG_VERSION"))
+	}
+
+	fn options(&self) -> Vec<EngineOption> {
+		vec![EngineOption {
+			name: "MoveTime",
+			default: 1000,
+			min: 1,
+			max: 3_600_000,
+		}]
+	}
+
+	fn set_option(&mut self, name: &str, value: i64) {
+		if name == "MoveTime" {
+			self.movetime = Duration::from_millis(value as u64);
+		}
+	}
+
+	fn search(&mut self, board: &Board, search: &mut Search) -> Option<Move> {
+		if board.is_terminal() {
+			return None;
+		}
+		let default_movetime = search.limits.is_empty().then_some(self.movetime);
+
+		let mut solver = Solver::new();
+		let mut next_report = Duration::from_secs(1);
+		// The first iteration is always run, to have a move.
+		loop {
+			solver.run_search_iteration(*board);
+			if !solver.iterations.is_multiple_of(CHECK_INTERVAL) && search.limits.nodes.is_none() {
+				continue;
+			}
+			if search.should_stop(solver.iterations as u64)
+				|| default_movetime.is_some_and(|movetime| search.elapsed() >= movetime)
+				|| search
+					.limits
+					.depth
+					.is_some_and(|depth| solver.current_best_continuation().len() >= depth as usize)
+			{
+				break;
+			}
+			if search.elapsed() >= next_report {
+				next_report += Duration::from_secs(1);
+				search.report(Alphacou::info(&solver, search));
+			}
+		}
+		search.report(Alphacou::info(&solver, search));
+		solver.current_best_move()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ascacou::{Engine, Limits};
+
+	#[test]
+	fn test_search() {
+		let board = Board::from_fen("2bbw/bww1w/w1w1w/1w1bw/wbb1b 013679ce").unwrap();
+		let mut infos = vec![];
+		let mut report = |info| infos.push(info);
+		let limits = Limits {
+			nodes: Some(500),
+			..Limits::default()
+		};
+		let mov =
+			Alphacou::default().search(&board, &mut Search::new(limits, &|| false, &mut report));
+		let info = infos.last().unwrap();
+		assert_eq!(info.nodes, 500);
+		assert_eq!(mov, info.pv.first().copied());
+		assert!(matches!(info.score, Some(Score::Mean(mean)) if (-1.0..=1.0).contains(&mean)));
+
+		let mut board = board;
+		while !board.is_terminal() {
+			let mov = board.possible_moves().next().unwrap();
+			board.play(mov).unwrap();
+		}
+		let mut report = |_| unreachable!();
+		assert_eq!(
+			Alphacou::default().search(&board, &mut Search::new(limits, &|| false, &mut report)),
+			None
+		);
+	}
+	#[test]
+	fn test_set_option() {
+		let mut alphacou = Alphacou::default();
+		alphacou.set_option("MoveTime", 200);
+		assert_eq!(alphacou.movetime, Duration::from_millis(200));
+		alphacou.set_option("Depth", 3);
+		assert_eq!(alphacou.movetime, Duration::from_millis(200));
+	}
+}
@@ -0,0 +1,167 @@
+use crate::play::GRACE;
+use ascacou::{Board, Engine, Move, Search};
+use rand::seq::IteratorRandom;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Time given to `uci` engines to answer `uci` once started.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Plays any legal move, as a baseline.
+pub struct Random;
+
+impl Engine for Random {
+	fn name(&self) -> String {
+		"random".to_string()
+	}
+
+	fn search(&mut self, board: &Board, _search: &mut Search) -> Option<Move> {
+		board.possible_moves().choose(&mut rand::rng())
+	}
+}
+
+/// An engine process talking the protocol of `ascacou::Engine`.
+pub struct Uci {
+	name: String,
+	child: Child,
+	stdin: ChildStdin,
+	/// Lines of the standard output of the process, read on another
+	/// thread to wait for them with a timeout.
+	lines: Receiver<String>,
+}
+
+impl Uci {
+	pub fn start(command: &[String]) -> io::Result<Uci> {
+		let mut child = Command::new(&command[0])
+			.args(&command[1..])
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()?;
+		let stdin = child.stdin.take().expect("stdin is piped");
+		let stdout = child.stdout.take().expect("stdout is piped");
+		let (sender, lines) = mpsc::channel();
+		thread::spawn(move || {
+			for line in BufReader::new(stdout).lines() {
+				let Ok(line) = line else { break };
+				if sender.send(line).is_err() {
+					break;
+				}
+			}
+		});
+
+		let mut engine = Uci {
+			name: command.join(" "),
+			child,
+			stdin,
+			lines,
+		};
+		engine.send("uci")?;
+		loop {
+			let line = engine.lines.recv_timeout(STARTUP_TIMEOUT).map_err(|_| {
+				io::Error::new(io::ErrorKind::TimedOut, "the engine did not answer uci")
+			})?;
+			if let Some(name) = line.strip_prefix("id name ") {
+				engine.name = name.to_string();
+			}
+			if line == "uciok" {
+				return Ok(engine);
+			}
+		}
+	}
+
+	fn send(&mut self, line: &str) -> io::Result<()> {
+		writeln!(self.stdin, "{}", line)?;
+		self.stdin.flush()
+	}
+}
+
+impl Engine for Uci {
+	fn name(&self) -> String {
+		self.name.clone()
+	}
+
+	fn new_game(&mut self) {
+		// A dead process forfeits on its first move.
+		let _ = self.send("ucinewgame");
+	}
+
+	/// `None` when the engine did not answer within the time limit
+	/// and its grace time.
+	fn search(&mut self, board: &Board, search: &mut Search) -> Option<Move> {
+		self.send(&format!("position fen {}", board.fen())).ok()?;
+		self.send(&format!("go {}", search.limits)).ok()?;
+		loop {
+			let line = match search.limits.movetime {
+				Some(movetime) => {
+					let timeout = (movetime + GRACE).saturating_sub(search.elapsed());
+					self.lines.recv_timeout(timeout).ok()?
+				}
+				None => self.lines.recv().ok()?,
+			};
+			if let Some(mov) = line.strip_prefix("bestmove ") {
+				return Move::try_from(mov.trim()).ok();
+			}
+		}
+	}
+}
+
+impl Drop for Uci {
+	fn drop(&mut self) {
+		let _ = self.send("quit");
+		// Engines still searching do not read `quit`.
+		let _ = self.child.kill();
+		let _ = self.child.wait();
+	}
+}
+
+/// An executable called for each move, like `minicou` before it had
+/// the protocol.
+pub struct Cli {
+	command: Vec<String>,
+}
+
+impl Cli {
+	pub fn new(command: &[String]) -> Cli {
+		Cli {
+			command: command.to_vec(),
+		}
+	}
+}
+
+impl Engine for Cli {
+	fn name(&self) -> String {
+		self.command.join(" ")
+	}
+
+	/// `None` when the process fails or does not print its move
+	/// within the time limit and its grace time.
+	fn search(&mut self, board: &Board, search: &mut Search) -> Option<Move> {
+		let movetime = search.limits.movetime.unwrap_or(Duration::from_secs(1));
+		let mut child = Command::new(&self.command[0])
+			.args(&self.command[1..])
+			.arg(format!("--timeout={}", movetime.as_millis()))
+			.arg(format!("--depth={}", search.limits.depth.unwrap_or(25)))
+			.arg(board.fen())
+			.stdout(Stdio::piped())
+			.spawn()
+			.ok()?;
+		let mut stdout = child.stdout.take().expect("stdout is piped");
+		let (sender, receiver) = mpsc::channel();
+		thread::spawn(move || {
+			let mut output = String::new();
+			let _ = stdout.read_to_string(&mut output);
+			let _ = sender.send(output);
+		});
+
+		let output = receiver.recv_timeout((movetime + GRACE).saturating_sub(search.elapsed()));
+		let _ = child.kill();
+		let _ = child.wait();
+		output.ok()?.lines().find_map(|line| {
+			let mov = line.strip_prefix("Move: ")?;
+			Move::try_from(mov.trim()).ok()
+		})
+	}
+}
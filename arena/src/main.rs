@@ -0,0 +1,331 @@
+mod engines;
+mod play;
+mod report;
+mod spec;
+mod stats;
+
+use ascacou::Board;
+use clap::Parser;
+use report::Summary;
+use spec::EngineSpec;
+use stats::{Record, Sprt};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::{Mutex, mpsc};
+use std::thread;
+use std::time::Duration;
+
+/// Play engines against each other from the benchmark openings, and
+/// print the scores of each pair of engines as markdown tables.
+///
+/// Each opening is played twice, each engine moving first once, and
+/// the scores of both games are summed. Scores are for the first
+/// engine of the pair, positive when it did better, followed by its
+/// Elo difference with the second engine.
+///
+/// Engines are given as:
+///
+///     minicou[:depth=<n>][,nodes=<n>]
+///     alphacou[:depth=<n>][,nodes=<n>]
+///     random
+///     uci:<command>    a process talking `minicou --uci`'s protocol
+///     cli:<command>    a process printing `Move: <move>` for a FEN,
+///                      like `minicou` before it had the protocol
+#[derive(Parser)]
+#[clap(about, author, version, verbatim_doc_comment)]
+struct Args {
+	/// Engines to compare, every pair of them plays a match
+	#[clap(num_args = 2.., required = true)]
+	engines: Vec<EngineSpec>,
+
+	/// Openings of each pair of engines, as <set>:<count> for the
+	/// first lines of each set
+	#[clap(
+		long,
+		value_delimiter = ',',
+		default_value = "endgame:10,midgame:5,earlygame:3,startgame:3"
+	)]
+	rounds: Vec<Rounds>,
+
+	/// Directory of the opening sets
+	#[clap(long, default_value = "benchmarks/data")]
+	data: PathBuf,
+
+	/// Time limit of each move in milliseconds, engines taking a
+	/// second more lose by forfeit
+	#[clap(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+	movetime: u64,
+
+	/// Number of games played at once, the number of CPUs by default
+	#[clap(short, long)]
+	jobs: Option<usize>,
+
+	/// Stop once a sequential probability ratio test tells whether
+	/// the first engine is better, as <elo0>,<elo1>[,<alpha>,<beta>]
+	/// to tell a difference of at most elo0 from at least elo1
+	#[clap(long)]
+	sprt: Option<Sprt>,
+
+	/// Print results as JSON
+	#[clap(long)]
+	json: bool,
+}
+
+/// Number of openings played from a set.
+#[derive(Clone, Debug)]
+struct Rounds {
+	set: String,
+	count: usize,
+}
+
+impl FromStr for Rounds {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Rounds, String> {
+		let (set, count) = input
+			.split_once(':')
+			.ok_or(format!("expected <set>:<count>, got '{}'", input))?;
+		Ok(Rounds {
+			set: set.to_string(),
+			count: count
+				.parse()
+				.map_err(|_| format!("count should be a number, got '{}'", count))?,
+		})
+	}
+}
+
+/// One game of a match.
+struct Job {
+	/// Index of the pair of engines.
+	pair: usize,
+	set: usize,
+	opening: usize,
+	board: Board,
+	/// Whether the second engine of the pair moves first.
+	swapped: bool,
+}
+
+fn load_openings(data: &Path, rounds: &Rounds) -> Result<Vec<Board>, String> {
+	let path = data.join(&rounds.set);
+	let content = std::fs::read_to_string(&path)
+		.map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+	content
+		.lines()
+		.take(rounds.count)
+		.map(|fen| {
+			let board = Board::from_fen(fen)
+				.map_err(|error| format!("{} in {}: {}", fen, path.display(), error))?;
+			board.validate().map_err(|violations| {
+				format!(
+					"{} in {}: invalid board: {}",
+					fen,
+					path.display(),
+					violations
+						.iter()
+						.map(|violation| violation.to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
+				)
+			})?;
+			Ok(board)
+		})
+		.collect()
+}
+
+fn run(args: Args) -> Result<(), String> {
+	if args.sprt.is_some() && args.engines.len() != 2 {
+		return Err("--sprt compares exactly two engines".to_string());
+	}
+	// Fail early rather than forfeiting every game.
+	for spec in &args.engines {
+		spec.start()
+			.map_err(|error| format!("cannot start {}: {}", spec, error))?;
+	}
+	let openings: Vec<Vec<Board>> = args
+		.rounds
+		.iter()
+		.map(|rounds| load_openings(&args.data, rounds))
+		.collect::<Result<_, _>>()?;
+	let engine_count = args.engines.len();
+	let pairs: Vec<(usize, usize)> = (0..engine_count)
+		.flat_map(|first| (first + 1..engine_count).map(move |second| (first, second)))
+		.collect();
+
+	let mut jobs = vec![];
+	for pair in 0..pairs.len() {
+		for (set, boards) in openings.iter().enumerate() {
+			for (opening, board) in boards.iter().enumerate() {
+				for swapped in [false, true] {
+					jobs.push(Job {
+						pair,
+						set,
+						opening,
+						board: *board,
+						swapped,
+					});
+				}
+			}
+		}
+	}
+	let game_count = jobs.len();
+	let jobs = Mutex::new(jobs.into_iter());
+	let threads = args
+		.jobs
+		.or_else(|| thread::available_parallelism().ok().map(usize::from))
+		.unwrap_or(1)
+		.max(1);
+	let movetime = Duration::from_millis(args.movetime);
+
+	// Scores of the first engine of each pair, by set and opening,
+	// for both games of the opening.
+	let mut scores: Vec<Vec<Vec<[Option<i8>; 2]>>> = vec![
+		openings
+			.iter()
+			.map(|boards| vec![[None; 2]; boards.len()])
+			.collect();
+		pairs.len()
+	];
+	let mut records = vec![Record::default(); pairs.len()];
+	thread::scope(|scope| {
+		let (sender, receiver) = mpsc::channel();
+		for _ in 0..threads {
+			let sender = sender.clone();
+			let (jobs, pairs, engines) = (&jobs, &pairs, &args.engines);
+			scope.spawn(move || {
+				loop {
+					let Some(job) = jobs.lock().unwrap().next() else {
+						break;
+					};
+					let (first, second) = pairs[job.pair];
+					let (first, second) = if job.swapped {
+						(&engines[second], &engines[first])
+					} else {
+						(&engines[first], &engines[second])
+					};
+					let outcome = match (first.start(), second.start()) {
+						(Ok(mut first_engine), Ok(mut second_engine)) => play::play(
+							&job.board,
+							[first_engine.as_mut(), second_engine.as_mut()],
+							[first.limits(movetime), second.limits(movetime)],
+						),
+						(first_engine, _) => {
+							let (loser, score) = if first_engine.is_err() {
+								(first, -play::FORFEIT_SCORE)
+							} else {
+								(second, play::FORFEIT_SCORE)
+							};
+							play::Outcome {
+								score,
+								forfeit: Some(format!("{} could not start", loser)),
+							}
+						}
+					};
+					if sender.send((job, outcome)).is_err() {
+						break;
+					}
+				}
+			});
+		}
+		drop(sender);
+
+		for (done, (job, outcome)) in receiver.into_iter().enumerate() {
+			let (first, second) = pairs[job.pair];
+			let (first, second) = if job.swapped {
+				(second, first)
+			} else {
+				(first, second)
+			};
+			eprintln!(
+				"[{}/{}] {} #{}: {} vs {}, {:+}{}",
+				done + 1,
+				game_count,
+				args.rounds[job.set].set,
+				job.opening + 1,
+				args.engines[first],
+				args.engines[second],
+				outcome.score,
+				outcome
+					.forfeit
+					.map(|reason| format!(" ({})", reason))
+					.unwrap_or_default()
+			);
+			let score = if job.swapped {
+				-outcome.score
+			} else {
+				outcome.score
+			};
+			scores[job.pair][job.set][job.opening][job.swapped as usize] = Some(score);
+			records[job.pair].add(score);
+
+			if let Some(sprt) = &args.sprt
+				&& let Some(hypothesis) = sprt.decision(&records[job.pair])
+			{
+				let mut jobs = jobs.lock().unwrap();
+				if jobs.len() > 0 {
+					eprintln!(
+						"SPRT: {:?} accepted after {} games, finishing running games",
+						hypothesis,
+						records[job.pair].games()
+					);
+					jobs.by_ref().for_each(drop);
+				}
+			}
+		}
+	});
+
+	let mut results = vec![];
+	for (pair, &(first, second)) in pairs.iter().enumerate() {
+		// Pairs of games cut short by the SPRT are left out.
+		let totals: Vec<Vec<i32>> = scores[pair]
+			.iter()
+			.map(|openings| {
+				openings
+					.iter()
+					.filter_map(|&[first, second]| Some(first? as i32 + second? as i32))
+					.collect()
+			})
+			.collect();
+		let sets: Vec<(&str, Summary)> = args
+			.rounds
+			.iter()
+			.zip(&totals)
+			.map(|(rounds, totals)| (rounds.set.as_str(), Summary::new(totals)))
+			.collect();
+		let overall = Summary::new(&totals.concat());
+		let (first, second) = (
+			args.engines[first].to_string(),
+			args.engines[second].to_string(),
+		);
+		if args.json {
+			results.push(report::json(
+				[&first, &second],
+				&sets,
+				overall,
+				&records[pair],
+				args.sprt.as_ref(),
+			));
+			continue;
+		}
+		println!("## {} vs {}\n", first, second);
+		println!("{}", report::table(&sets, overall));
+		println!("{}", report::results(&records[pair]));
+		if let Some(sprt) = &args.sprt {
+			println!("{}\n", report::sprt(sprt, &records[pair]));
+		}
+	}
+	if args.json {
+		println!("{}", serde_json::json!({ "pairs": results }));
+	}
+	Ok(())
+}
+
+fn main() -> ExitCode {
+	match run(Args::parse()) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(message) => {
+			eprintln!("{}", message);
+			ExitCode::FAILURE
+		}
+	}
+}
@@ -0,0 +1,147 @@
+use ascacou::{Board, Engine, Info, Limits, Search};
+use std::time::{Duration, Instant};
+
+/// Time engines may use over the time limit of a move before they
+/// lose by forfeit.
+pub const GRACE: Duration = Duration::from_secs(1);
+
+/// Score of a forfeited game, a loss by every tile of a player.
+pub const FORFEIT_SCORE: i8 = 8;
+
+/// How a game ended.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Outcome {
+	/// Final score for the engine moving first, the difference of
+	/// tiles between both players.
+	pub score: i8,
+	/// Why the game ended before the board was full, if it did.
+	pub forfeit: Option<String>,
+}
+
+/**
+ * Play a game from `opening`, the first engine being the player to
+ * move. Each engine searches with its own limits.
+ *
+ * Engines lose by forfeit when they take longer than the time limit
+ * of a move and `GRACE`, when they play an illegal move, or when
+ * they find no move while there is one.
+ */
+pub fn play(opening: &Board, mut engines: [&mut dyn Engine; 2], limits: [Limits; 2]) -> Outcome {
+	for engine in engines.iter_mut() {
+		engine.new_game();
+	}
+	let mut board = *opening;
+	let mut turn = 0;
+	while !board.is_terminal() {
+		let start = Instant::now();
+		let mut report = |_: Info| ();
+		let mov = engines[turn].search(
+			&board,
+			&mut Search::new(limits[turn], &|| false, &mut report),
+		);
+		let elapsed = start.elapsed();
+		let forfeit = match mov {
+			_ if limits[turn]
+				.movetime
+				.is_some_and(|movetime| elapsed > movetime + GRACE) =>
+			{
+				Some(format!("took {}ms", elapsed.as_millis()))
+			}
+			None => Some("found no move".to_string()),
+			Some(mov) => board
+				.play(mov)
+				.err()
+				.map(|error| format!("played {}: {}", mov, error)),
+		};
+		if let Some(reason) = forfeit {
+			let score = if turn == 0 {
+				-FORFEIT_SCORE
+			} else {
+				FORFEIT_SCORE
+			};
+			return Outcome {
+				score,
+				forfeit: Some(format!("{} {}", engines[turn].name(), reason)),
+			};
+		}
+		turn = 1 - turn;
+	}
+
+	let score = board.current_score();
+	Outcome {
+		score: if turn == 0 { score } else { -score },
+		forfeit: None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::engines::Random;
+	use ascacou::Move;
+
+	/// Plays the first legal move of the board, or `mov` once.
+	struct FirstMove {
+		mov: Option<Move>,
+	}
+
+	impl Engine for FirstMove {
+		fn name(&self) -> String {
+			"first move".to_string()
+		}
+
+		fn search(&mut self, board: &Board, _search: &mut Search) -> Option<Move> {
+			self.mov.take().or_else(|| board.possible_moves().next())
+		}
+	}
+
+	#[test]
+	fn test_play() {
+		let opening = Board::from_fen("2bbw/bww1w/w1w1w/1w1bw/wbb1b 013679ce").unwrap();
+		let limits = [Limits::default(); 2];
+		let outcome = play(&opening, [&mut Random, &mut Random], limits);
+		assert_eq!(outcome.forfeit, None);
+		assert!((-8..=8).contains(&outcome.score));
+
+		// Playing the first legal move on both sides is deterministic.
+		let mut board = opening;
+		let mut plies = 0;
+		while !board.is_terminal() {
+			let mov = board.possible_moves().next().unwrap();
+			board.play(mov).unwrap();
+			plies += 1;
+		}
+		let first = play(
+			&opening,
+			[&mut FirstMove { mov: None }, &mut FirstMove { mov: None }],
+			limits,
+		);
+		assert_eq!(first.forfeit, None);
+		assert_eq!(
+			first.score,
+			if plies % 2 == 0 {
+				board.current_score()
+			} else {
+				-board.current_score()
+			}
+		);
+	}
+
+	#[test]
+	fn test_forfeit() {
+		let opening = Board::from_fen("2bbw/bww1w/w1w1w/1w1bw/wbb1b 013679ce").unwrap();
+		let illegal = Move::black(2, 0);
+		let outcome = play(
+			&opening,
+			[&mut Random, &mut FirstMove { mov: Some(illegal) }],
+			[Limits::default(); 2],
+		);
+		assert_eq!(outcome.score, 8);
+		assert!(
+			outcome
+				.forfeit
+				.unwrap()
+				.starts_with("first move played bc1")
+		);
+	}
+}
@@ -0,0 +1,225 @@
+use crate::stats::{Estimate, Hypothesis, Record, Sprt};
+use serde_json::{Value, json};
+
+/// Mean and standard deviation of the scores of color-swapped pairs
+/// of games.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Summary {
+	pub pairs: usize,
+	pub mean: f64,
+	pub std_dev: f64,
+}
+
+impl Summary {
+	/// `scores` are the sums of both games of each pair. The standard
+	/// deviation is the population one, as in the tourney workflow.
+	pub fn new(scores: &[i32]) -> Summary {
+		let pairs = scores.len();
+		if pairs == 0 {
+			return Summary {
+				pairs,
+				mean: 0.0,
+				std_dev: 0.0,
+			};
+		}
+		let mean = scores.iter().sum::<i32>() as f64 / pairs as f64;
+		let variance = scores
+			.iter()
+			.map(|&score| (score as f64 - mean).powi(2))
+			.sum::<f64>()
+			/ pairs as f64;
+		Summary {
+			pairs,
+			mean,
+			std_dev: variance.sqrt(),
+		}
+	}
+}
+
+/**
+ * A markdown table of the summary of each set, followed by the
+ * overall summary, as in `docs/Forced-Moves-Exploration.md`:
+ *
+ * ```text
+ * | set         | pairs | average score | std dev |
+ * | :---------- | ----: | ------------: | ------: |
+ * | endgame     |    10 |          0.00 |    0.00 |
+ * | **Overall** |    10 |      **0.00** | **0.00** |
+ * ```
+ */
+pub fn table(sets: &[(&str, Summary)], overall: Summary) -> String {
+	let mut table = String::from(
+		"| set         | pairs | average score | std dev |\n\
+		 | :---------- | ----: | ------------: | ------: |\n",
+	);
+	for (set, summary) in sets {
+		table.push_str(&format!(
+			"| {:<11} | {:>5} | {:>13.2} | {:>7.2} |\n",
+			set, summary.pairs, summary.mean, summary.std_dev
+		));
+	}
+	table.push_str(&format!(
+		"| {:<11} | {:>5} | {:>13} | {:>7} |\n",
+		"**Overall**",
+		overall.pairs,
+		format!("**{:.2}**", overall.mean),
+		format!("**{:.2}**", overall.std_dev)
+	));
+	table
+}
+
+/// A markdown table of the results of an engine, with its Elo
+/// difference and mean final score against its opponent.
+pub fn results(record: &Record) -> String {
+	let elo = record.elo();
+	let margin = record.margin();
+	format!(
+		"| games | wins | draws | losses |  score |    elo | elo 95% interval |   tile margin |\n\
+		 | ----: | ---: | ----: | -----: | -----: | -----: | ---------------: | ------------: |\n\
+		 | {:>5} | {:>4} | {:>5} | {:>6} | {:>5.1}% | {:>+6.1} | {:>16} | {:>13} |\n",
+		record.games(),
+		record.wins,
+		record.draws,
+		record.losses,
+		record.score() * 100.0,
+		elo.value,
+		format!("[{:+.1}, {:+.1}]", elo.low, elo.high),
+		format!(
+			"{:+.2} ± {:.2}",
+			margin.value,
+			(margin.high - margin.low) / 2.0
+		),
+	)
+}
+
+/// The state of `sprt` for an engine, e.g.
+/// `SPRT [0, 10] (α 0.05, β 0.05): LLR 1.20 in [-2.94, 2.94], undecided`.
+pub fn sprt(sprt: &Sprt, record: &Record) -> String {
+	let (lower, upper) = sprt.bounds();
+	format!(
+		"SPRT [{}, {}] (α {}, β {}): LLR {:.2} in [{:.2}, {:.2}], {}",
+		sprt.elo0,
+		sprt.elo1,
+		sprt.alpha,
+		sprt.beta,
+		sprt.llr(record),
+		lower,
+		upper,
+		match sprt.decision(record) {
+			Some(Hypothesis::H1) => "H1 accepted, better",
+			Some(Hypothesis::H0) => "H0 accepted, not better",
+			None => "undecided",
+		}
+	)
+}
+
+fn summary_json(summary: &Summary) -> Value {
+	json!({
+		"pairs": summary.pairs,
+		"mean": summary.mean,
+		"std_dev": summary.std_dev,
+	})
+}
+
+/// Infinite values, for matches without any loss or win, are null.
+fn estimate_json(estimate: &Estimate) -> Value {
+	json!({
+		"value": estimate.value,
+		"low": estimate.low,
+		"high": estimate.high,
+	})
+}
+
+/// Everything the tables hold, for the first engine of a pair.
+pub fn json(
+	engines: [&str; 2],
+	sets: &[(&str, Summary)],
+	overall: Summary,
+	record: &Record,
+	sprt: Option<&Sprt>,
+) -> Value {
+	json!({
+		"engines": engines,
+		"sets": sets
+			.iter()
+			.map(|(set, summary)| json!({ "set": set, "summary": summary_json(summary) }))
+			.collect::<Vec<Value>>(),
+		"overall": summary_json(&overall),
+		"games": record.games(),
+		"wins": record.wins,
+		"draws": record.draws,
+		"losses": record.losses,
+		"score": record.score(),
+		"elo": estimate_json(&record.elo()),
+		"margin": estimate_json(&record.margin()),
+		"sprt": sprt.map(|sprt| {
+			let (lower, upper) = sprt.bounds();
+			json!({
+				"elo0": sprt.elo0,
+				"elo1": sprt.elo1,
+				"alpha": sprt.alpha,
+				"beta": sprt.beta,
+				"llr": sprt.llr(record),
+				"lower": lower,
+				"upper": upper,
+				"accepted": sprt.decision(record).map(|hypothesis| format!("{:?}", hypothesis)),
+			})
+		}),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_summary() {
+		assert_eq!(
+			Summary::new(&[2, 0, -1, 3]),
+			Summary {
+				pairs: 4,
+				mean: 1.0,
+				std_dev: 1.5811388300841898
+			}
+		);
+		assert_eq!(Summary::new(&[]).mean, 0.0);
+
+		let table = table(
+			&[
+				("endgame", Summary::new(&[0, 0])),
+				("midgame", Summary::new(&[1, -2])),
+			],
+			Summary::new(&[0, 0, 1, -2]),
+		);
+		assert_eq!(
+			table.lines().nth(3).unwrap(),
+			"| midgame     |     2 |         -0.50 |    1.50 |"
+		);
+		assert_eq!(
+			table.lines().last().unwrap(),
+			"| **Overall** |     4 |     **-0.25** | **1.09** |"
+		);
+	}
+
+	#[test]
+	fn test_results() {
+		let mut record = Record::default();
+		for score in [2, 2, 0, -1] {
+			record.add(score);
+		}
+		assert_eq!(
+			results(&record).lines().last().unwrap(),
+			"|     4 |    2 |     1 |      1 |  62.5% |  +88.7 |   [-221.2, +inf] |  +0.75 ± 1.27 |"
+		);
+
+		let sprt: Sprt = "0,10".parse().unwrap();
+		assert_eq!(
+			super::sprt(&sprt, &record),
+			"SPRT [0, 10] (α 0.05, β 0.05): LLR 0.04 in [-2.94, 2.94], undecided"
+		);
+		let json = json(["a", "b"], &[], Summary::new(&[3]), &record, Some(&sprt));
+		assert_eq!(json["elo"]["high"], Value::Null);
+		assert_eq!(json["sprt"]["accepted"], Value::Null);
+		assert_eq!(json["overall"]["mean"], 3.0);
+	}
+}
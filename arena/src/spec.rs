@@ -0,0 +1,158 @@
+use crate::engines::{Cli, Random, Uci};
+use alphacou::Alphacou;
+use ascacou::{Engine, Limits};
+use minicou::Minicou;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+/**
+ * An engine taking part in a match, parsed from the command line:
+ *
+ * ```text
+ * minicou[:depth=<n>][,nodes=<n>]
+ * alphacou[:depth=<n>][,nodes=<n>]
+ * random
+ * uci:<command>
+ * cli:<command>
+ * ```
+ *
+ * `uci` engines are processes talking the protocol of
+ * `ascacou::Engine`, such as `uci:minicou --uci`. `cli` engines are
+ * processes called for each move with `--timeout=<ms> --depth=<n>
+ * <fen>`, printing `Move: <move>`, like the `minicou` command line
+ * of versions older than the protocol.
+ */
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct EngineSpec {
+	/// The spec as given, to name the engine in results.
+	name: String,
+	kind: EngineKind,
+	/// Limits of each search, on top of the time limit of the match.
+	limits: Limits,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum EngineKind {
+	Minicou,
+	Alphacou,
+	Random,
+	Uci(Vec<String>),
+	Cli(Vec<String>),
+}
+
+impl EngineSpec {
+	/// Limits of each search, given the time limit of each move.
+	pub fn limits(&self, movetime: Duration) -> Limits {
+		Limits {
+			movetime: Some(movetime),
+			..self.limits
+		}
+	}
+
+	/// A new instance of the engine, starting its process for `uci`
+	/// engines.
+	pub fn start(&self) -> io::Result<Box<dyn Engine>> {
+		Ok(match &self.kind {
+			EngineKind::Minicou => Box::new(Minicou::default()),
+			EngineKind::Alphacou => Box::new(Alphacou::default()),
+			EngineKind::Random => Box::new(Random),
+			EngineKind::Uci(command) => Box::new(Uci::start(command)?),
+			EngineKind::Cli(command) => Box::new(Cli::new(command)),
+		})
+	}
+}
+
+impl std::fmt::Display for EngineSpec {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.name)
+	}
+}
+
+impl FromStr for EngineSpec {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<EngineSpec, String> {
+		let (name, options) = input.split_once(':').unwrap_or((input, ""));
+		let command = || {
+			let command: Vec<String> = options.split_whitespace().map(String::from).collect();
+			if command.is_empty() {
+				Err(format!("expected {}:<command>", name))
+			} else {
+				Ok(command)
+			}
+		};
+		let kind = match name {
+			"minicou" => EngineKind::Minicou,
+			"alphacou" => EngineKind::Alphacou,
+			"random" => EngineKind::Random,
+			"uci" => EngineKind::Uci(command()?),
+			"cli" => EngineKind::Cli(command()?),
+			_ => {
+				return Err(format!(
+					"unknown engine '{}', expected minicou, alphacou, random, uci or cli",
+					name
+				));
+			}
+		};
+
+		let mut limits = Limits::default();
+		if matches!(kind, EngineKind::Minicou | EngineKind::Alphacou) {
+			for option in options.split(',').filter(|option| !option.is_empty()) {
+				let (key, value) = option
+					.split_once('=')
+					.ok_or(format!("expected <option>=<value>, got '{}'", option))?;
+				let error = || format!("{} should be a positive number, got '{}'", key, value);
+				match key {
+					"depth" => limits.depth = Some(value.parse().map_err(|_| error())?),
+					"nodes" => limits.nodes = Some(value.parse().map_err(|_| error())?),
+					_ => return Err(format!("unknown option '{}'", key)),
+				}
+			}
+		} else if name == "random" && !options.is_empty() {
+			return Err("random takes no option".to_string());
+		}
+
+		Ok(EngineSpec {
+			name: input.to_string(),
+			kind,
+			limits,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		let spec: EngineSpec = "minicou:depth=6,nodes=1000".parse().unwrap();
+		assert_eq!(spec.to_string(), "minicou:depth=6,nodes=1000");
+		assert_eq!(
+			spec.limits(Duration::from_millis(200)),
+			Limits {
+				depth: Some(6),
+				movetime: Some(Duration::from_millis(200)),
+				nodes: Some(1000),
+				infinite: false,
+			}
+		);
+		assert_eq!(
+			"uci:./old/minicou --uci"
+				.parse::<EngineSpec>()
+				.unwrap()
+				.kind,
+			EngineKind::Uci(vec!["./old/minicou".to_string(), "--uci".to_string()])
+		);
+		assert_eq!(
+			"alphacou".parse::<EngineSpec>().unwrap().limits,
+			Limits::default()
+		);
+		assert!("minicou:depth=-1".parse::<EngineSpec>().is_err());
+		assert!("minicou:time=10".parse::<EngineSpec>().is_err());
+		assert!("random:depth=2".parse::<EngineSpec>().is_err());
+		assert!("cli:".parse::<EngineSpec>().is_err());
+		assert!("stockfish".parse::<EngineSpec>().is_err());
+	}
+}
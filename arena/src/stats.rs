@@ -0,0 +1,264 @@
+use std::str::FromStr;
+
+/// Quantile of the normal distribution for 95% confidence intervals.
+const Z_95: f64 = 1.959964;
+
+/// Results of the games of an engine against another.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Record {
+	pub wins: u32,
+	pub draws: u32,
+	pub losses: u32,
+	/// Sum of the final scores, a difference of tiles.
+	margin_sum: i64,
+	/// Sum of the squares of the final scores.
+	margin_squares: i64,
+}
+
+/// A value with its 95% confidence interval.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Estimate {
+	pub value: f64,
+	pub low: f64,
+	pub high: f64,
+}
+
+impl Record {
+	/// Count a game with the final `score` of the engine.
+	pub fn add(&mut self, score: i8) {
+		match score {
+			1.. => self.wins += 1,
+			0 => self.draws += 1,
+			..0 => self.losses += 1,
+		}
+		self.margin_sum += score as i64;
+		self.margin_squares += (score as i64).pow(2);
+	}
+
+	pub fn games(&self) -> u32 {
+		self.wins + self.draws + self.losses
+	}
+
+	/// Points per game, a win being worth 1 and a draw 0.5.
+	pub fn score(&self) -> f64 {
+		if self.games() == 0 {
+			return 0.5;
+		}
+		(self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+	}
+
+	/// Variance of the points of a game.
+	fn variance(&self) -> f64 {
+		if self.games() == 0 {
+			return 0.0;
+		}
+		let score = self.score();
+		(self.wins as f64 * (1.0 - score).powi(2)
+			+ self.draws as f64 * (0.5 - score).powi(2)
+			+ self.losses as f64 * score.powi(2))
+			/ self.games() as f64
+	}
+
+	/// Elo difference with the opponent, infinite when every game was
+	/// won or lost.
+	pub fn elo(&self) -> Estimate {
+		let error = Z_95 * (self.variance() / self.games().max(1) as f64).sqrt();
+		let score = self.score();
+		Estimate {
+			value: elo(score),
+			low: elo(score - error),
+			high: elo(score + error),
+		}
+	}
+
+	/// Mean final score, in tiles.
+	pub fn margin(&self) -> Estimate {
+		let games = self.games().max(1) as f64;
+		let mean = self.margin_sum as f64 / games;
+		let variance = self.margin_squares as f64 / games - mean.powi(2);
+		let error = Z_95 * (variance.max(0.0) / games).sqrt();
+		Estimate {
+			value: mean,
+			low: mean - error,
+			high: mean + error,
+		}
+	}
+}
+
+/// Elo difference giving an expected `score`, see `expected_score`.
+fn elo(score: f64) -> f64 {
+	let score = score.clamp(0.0, 1.0);
+	-400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Points per game expected against an opponent `elo` points weaker.
+fn expected_score(elo: f64) -> f64 {
+	1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/**
+ * Sequential probability ratio test, telling after each game whether
+ * an engine is at least `elo1` points stronger than its opponent
+ * (H1), or at most `elo0` points (H0), or whether more games are
+ * needed. `alpha` and `beta` are the probabilities of wrongly
+ * accepting H1 and H0.
+ *
+ * Parsed from `<elo0>,<elo1>[,<alpha>,<beta>]`, with both
+ * probabilities 0.05 by default.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Sprt {
+	pub elo0: f64,
+	pub elo1: f64,
+	pub alpha: f64,
+	pub beta: f64,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Hypothesis {
+	H0,
+	H1,
+}
+
+impl Sprt {
+	/// Log-likelihood ratio of H1 against H0, using the normal
+	/// approximation of the points of a game.
+	pub fn llr(&self, record: &Record) -> f64 {
+		let variance = record.variance();
+		// Identical results say nothing about their spread.
+		if variance == 0.0 {
+			return 0.0;
+		}
+		let (score0, score1) = (expected_score(self.elo0), expected_score(self.elo1));
+		record.games() as f64 * (score1 - score0) * (2.0 * record.score() - score0 - score1)
+			/ (2.0 * variance)
+	}
+
+	/// The ratio accepts H0 below the lower bound and H1 above the
+	/// upper one.
+	pub fn bounds(&self) -> (f64, f64) {
+		(
+			(self.beta / (1.0 - self.alpha)).ln(),
+			((1.0 - self.beta) / self.alpha).ln(),
+		)
+	}
+
+	/// The accepted hypothesis, if any.
+	pub fn decision(&self, record: &Record) -> Option<Hypothesis> {
+		let llr = self.llr(record);
+		let (lower, upper) = self.bounds();
+		if llr >= upper {
+			Some(Hypothesis::H1)
+		} else if llr <= lower {
+			Some(Hypothesis::H0)
+		} else {
+			None
+		}
+	}
+}
+
+impl FromStr for Sprt {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Sprt, String> {
+		let numbers = input
+			.split(',')
+			.map(|number| number.trim().parse::<f64>())
+			.collect::<Result<Vec<f64>, _>>()
+			.map_err(|_| format!("expected numbers, got '{}'", input))?;
+		let sprt = match numbers[..] {
+			[elo0, elo1] => Sprt {
+				elo0,
+				elo1,
+				alpha: 0.05,
+				beta: 0.05,
+			},
+			[elo0, elo1, alpha, beta] => Sprt {
+				elo0,
+				elo1,
+				alpha,
+				beta,
+			},
+			_ => {
+				return Err(format!(
+					"expected <elo0>,<elo1>[,<alpha>,<beta>], got '{}'",
+					input
+				));
+			}
+		};
+		if sprt.elo0 >= sprt.elo1 {
+			return Err("elo0 should be lower than elo1".to_string());
+		}
+		let probability = |p: f64| p > 0.0 && p < 0.5;
+		if !probability(sprt.alpha) || !probability(sprt.beta) {
+			return Err("alpha and beta should be between 0 and 0.5".to_string());
+		}
+		Ok(sprt)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn record(wins: u32, draws: u32, losses: u32) -> Record {
+		let mut record = Record::default();
+		for _ in 0..wins {
+			record.add(2);
+		}
+		for _ in 0..draws {
+			record.add(0);
+		}
+		for _ in 0..losses {
+			record.add(-1);
+		}
+		record
+	}
+
+	#[test]
+	fn test_elo() {
+		let even = record(10, 10, 10);
+		assert_eq!(even.score(), 0.5);
+		assert_eq!(even.elo().value, 0.0);
+		assert!(even.elo().low < 0.0 && even.elo().high > 0.0);
+
+		let better = record(50, 20, 30);
+		assert_eq!(better.score(), 0.6);
+		assert!((better.elo().value - 70.44).abs() < 0.01);
+		assert!(better.elo().low > 0.0);
+		assert!(better.elo().high < 150.0);
+		assert!((expected_score(better.elo().value) - 0.6).abs() < 1e-9);
+
+		assert_eq!(record(3, 0, 0).elo().value, f64::INFINITY);
+
+		let margin = record(50, 20, 30).margin();
+		assert_eq!(margin.value, 0.7);
+		assert!(margin.low < 0.7 && margin.high > 0.7);
+	}
+
+	#[test]
+	fn test_sprt() {
+		let sprt: Sprt = "0,20".parse().unwrap();
+		assert_eq!(sprt.alpha, 0.05);
+		let (lower, upper) = sprt.bounds();
+		assert!((lower + 2.944).abs() < 0.001 && (upper - 2.944).abs() < 0.001);
+
+		assert_eq!(sprt.decision(&record(5, 5, 5)), None);
+		assert_eq!(sprt.decision(&record(300, 100, 100)), Some(Hypothesis::H1));
+		assert_eq!(sprt.decision(&record(100, 100, 300)), Some(Hypothesis::H0));
+		assert_eq!(sprt.llr(&record(0, 40, 0)), 0.0);
+
+		assert_eq!(
+			"-5,5,0.1,0.2".parse(),
+			Ok(Sprt {
+				elo0: -5.0,
+				elo1: 5.0,
+				alpha: 0.1,
+				beta: 0.2
+			})
+		);
+		assert!("10,0".parse::<Sprt>().is_err());
+		assert!("0,10,0,0.05".parse::<Sprt>().is_err());
+		assert!("0".parse::<Sprt>().is_err());
+	}
+}
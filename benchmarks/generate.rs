@@ -33,7 +33,7 @@ fn generate_line<R: Rng>(
 		let mov = possible_moves
 			.choose(rng)
 			.ok_or("There should be at least one possible move")?;
-		board = board.next(&mov).expect("should generate a valid board");
+		board = board.next(mov).expect("should generate a valid board");
 		possible_moves = board.possible_moves().collect();
 	}
 
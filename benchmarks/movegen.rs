@@ -0,0 +1,94 @@
+pub(crate) mod utils;
+
+use ascacou::{Board, Color, Move};
+use std::time::{Duration, Instant};
+
+use crate::utils::{Alignment, FILES, generate_table};
+
+type SimpleResult<T> = Result<T, &'static str>;
+
+/// Times each set of positions is walked through.
+const ITERATIONS: usize = 1_000;
+
+/// Legal moves checked square by square, as `Board::possible_moves`
+/// used to do before legal move masks.
+fn square_by_square(board: &Board) -> usize {
+	let mut count = 0;
+	for x in 0..5 {
+		for y in 0..5 {
+			for color in [Color::Black, Color::White] {
+				if board.is_move_possible(&Move::new(x, y, color)) {
+					count += 1;
+				}
+			}
+		}
+	}
+	count
+}
+
+fn masks(board: &Board) -> usize {
+	let (black_legal, white_legal) = board.legal_moves();
+	(black_legal.count_ones() + white_legal.count_ones()) as usize
+}
+
+fn measure(boards: &[Board], generator: fn(&Board) -> usize) -> (usize, Duration) {
+	let time = Instant::now();
+	let mut count = 0;
+	for _ in 0..ITERATIONS {
+		count += boards
+			.iter()
+			.map(|board| generator(std::hint::black_box(board)))
+			.sum::<usize>();
+	}
+	(count / ITERATIONS, time.elapsed())
+}
+
+fn run_group(file: &str) -> SimpleResult<[String; 5]> {
+	let content = std::fs::read_to_string(std::path::Path::new("benchmarks/data").join(file))
+		.map_err(|_| "Could not read benchmark file")?;
+	let boards = content
+		.lines()
+		.map(Board::from_fen)
+		.collect::<Result<Vec<Board>, _>>()
+		.map_err(|_| "Could not parse FEN")?;
+
+	let (square_count, square_duration) = measure(&boards, square_by_square);
+	let (masks_count, masks_duration) = measure(&boards, masks);
+	if square_count != masks_count {
+		return Err("Generators disagree");
+	}
+	Ok([
+		file.to_string(),
+		masks_count.to_string(),
+		format!("{:.2?}", square_duration),
+		format!("{:.2?}", masks_duration),
+		format!(
+			"{:.2}x",
+			square_duration.as_secs_f64() / masks_duration.as_secs_f64()
+		),
+	])
+}
+
+fn main() -> SimpleResult<()> {
+	let alignments = [
+		Alignment::Default,
+		Alignment::Right,
+		Alignment::Right,
+		Alignment::Right,
+		Alignment::Right,
+	];
+	let headers = ["set", "moves", "square by square", "masks", "speedup"].map(String::from);
+	let body = FILES
+		.iter()
+		.map(|&file| {
+			println!("Generating moves of {file} set {ITERATIONS} times");
+			run_group(file)
+		})
+		.collect::<SimpleResult<Vec<[String; 5]>>>()?;
+
+	println!(
+		"{}",
+		String::from_utf8_lossy(&generate_table(alignments, headers, body))
+	);
+	Ok(())
+}
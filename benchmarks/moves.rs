@@ -0,0 +1,110 @@
+pub(crate) mod utils;
+
+use ascacou::{Board, Color, Move};
+use std::time::{Duration, Instant};
+
+use crate::utils::{Alignment, FILES, generate_table};
+
+type SimpleResult<T> = Result<T, &'static str>;
+
+/// Positions taken from each benchmark set.
+const POSITIONS: usize = 10;
+/// Depth of the explored tree from each position.
+const DEPTH: u8 = 3;
+
+fn all_moves() -> Vec<Move> {
+	(0..5)
+		.flat_map(|x| (0..5).map(move |y| (x, y)))
+		.flat_map(|(x, y)| [Move::new(x, y, Color::Black), Move::new(x, y, Color::White)])
+		.collect()
+}
+
+/// Count leaves, copying a new board at every node.
+fn walk_copy(board: &Board, moves: &[Move], depth: u8) -> u64 {
+	if depth == 0 {
+		return 1;
+	}
+	moves
+		.iter()
+		.filter_map(|mov| board.next(mov))
+		.map(|next| walk_copy(&next, moves, depth - 1))
+		.sum()
+}
+
+/// Count leaves, playing and taking back moves on a single board.
+fn walk_in_place(board: &mut Board, moves: &[Move], depth: u8) -> u64 {
+	if depth == 0 {
+		return 1;
+	}
+	let mut leaves = 0;
+	for mov in moves {
+		if let Ok(undo) = board.play(*mov) {
+			leaves += walk_in_place(board, moves, depth - 1);
+			board.undo(undo);
+		}
+	}
+	leaves
+}
+
+fn run_group(file: &str, moves: &[Move]) -> SimpleResult<(u64, Duration, Duration)> {
+	let content = std::fs::read_to_string(std::path::Path::new("benchmarks/data").join(file))
+		.map_err(|_| "Could not read benchmark file")?;
+	let boards = content
+		.lines()
+		.take(POSITIONS)
+		.map(Board::from_fen)
+		.collect::<Result<Vec<Board>, _>>()
+		.map_err(|_| "Could not parse FEN")?;
+
+	let time = Instant::now();
+	let leaves: u64 = boards
+		.iter()
+		.map(|board| walk_copy(board, moves, DEPTH))
+		.sum();
+	let copy_duration = time.elapsed();
+
+	let time = Instant::now();
+	let in_place_leaves: u64 = boards
+		.iter()
+		.copied()
+		.map(|mut board| walk_in_place(&mut board, moves, DEPTH))
+		.sum();
+	let in_place_duration = time.elapsed();
+
+	if leaves != in_place_leaves {
+		return Err("Copy and in place walks disagree");
+	}
+	Ok((leaves, copy_duration, in_place_duration))
+}
+
+fn main() -> SimpleResult<()> {
+	let moves = all_moves();
+	let alignments = [
+		Alignment::Default,
+		Alignment::Right,
+		Alignment::Right,
+		Alignment::Right,
+		Alignment::Right,
+	];
+	let headers = ["set", "leaves", "copy", "in place", "speedup"].map(String::from);
+	let body = FILES
+		.iter()
+		.map(|&file| {
+			println!("Walking {file} set to depth {DEPTH}");
+			let (leaves, copy, in_place) = run_group(file, &moves)?;
+			Ok([
+				file.to_string(),
+				leaves.to_string(),
+				format!("{:.2?}", copy),
+				format!("{:.2?}", in_place),
+				format!("{:.2}x", copy.as_secs_f64() / in_place.as_secs_f64()),
+			])
+		})
+		.collect::<SimpleResult<Vec<[String; 5]>>>()?;
+
+	println!(
+		"{}",
+		String::from_utf8_lossy(&generate_table(alignments, headers, body))
+	);
+	Ok(())
+}
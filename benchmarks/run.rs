@@ -3,7 +3,7 @@ pub(crate) mod utils;
 use std::io::Write;
 use std::time::{Duration, Instant};
 
-use crate::utils::{FILES, MAX_DEPTHS};
+use crate::utils::{Alignment, FILES, MAX_DEPTHS, generate_table};
 
 type SimpleResult<T> = Result<T, &'static str>;
 type EmptyResult = SimpleResult<()>;
@@ -121,70 +121,6 @@ fn iterations() -> impl Iterator<Item = (bool, &'static str, u8)> + 'static {
 		.map(|(partial, (&file, &depth))| (partial, file, depth))
 }
 
-enum Alignment {
-	#[allow(dead_code)]
-	Left,
-	#[allow(dead_code)]
-	Center,
-	Right,
-	Default,
-}
-
-fn generate_table(
-	alignments: [Alignment; 5],
-	headers: [String; 5],
-	body: Vec<[String; 5]>,
-) -> Vec<u8> {
-	let mut buffer = Vec::new();
-	let lengths: [usize; 5] = [headers.clone()]
-		.iter()
-		.chain(body.iter())
-		.map(|[a, b, c, d, e]| [a.len(), b.len(), c.len(), d.len(), e.len()])
-		.fold(
-			[0; 5],
-			|[acc_a, acc_b, acc_c, acc_d, acc_e], [len_a, len_b, len_c, len_d, len_e]| {
-				[
-					acc_a.max(len_a),
-					acc_b.max(len_b),
-					acc_c.max(len_c),
-					acc_d.max(len_d),
-					acc_e.max(len_e),
-				]
-			},
-		);
-	// format!("{:-<l$}", "")
-	let underlines: Vec<String> = lengths
-		.iter()
-		.zip(&alignments)
-		.map(|(l, a)| match a {
-			Alignment::Left => format!(":{:-<width$}", "-", width = l - 1),
-			Alignment::Center => format!(":{:-<width$}:", "-", width = l - 2),
-			Alignment::Right => format!("{:-<width$}:", "-", width = l - 1),
-			Alignment::Default => format!("{:-<width$}", "-", width = l),
-		})
-		.collect();
-
-	[headers]
-		.iter()
-		.chain([underlines.try_into().unwrap()].iter())
-		.chain(body.iter())
-		.zip([lengths].iter().cycle())
-		.zip([&alignments].iter().cycle())
-		.for_each(|((cells, lens), alignments)| {
-			for i in 0..5 {
-				let (cell, len) = (&cells[i], lens[i]);
-				match alignments[i] {
-					Alignment::Left => write!(&mut buffer, "| {cell:<len$} ").ok(),
-					Alignment::Center => write!(&mut buffer, "| {cell:^len$} ").ok(),
-					Alignment::Right => write!(&mut buffer, "| {cell:>len$} ").ok(),
-					Alignment::Default => write!(&mut buffer, "| {cell:len$} ").ok(),
-				};
-			}
-			write!(&mut buffer, "|\n").ok();
-		});
-	buffer
-}
-
 fn write_to_readme(table: Vec<u8>) -> EmptyResult {
 	let magic_string = "<!-- AUTOMAGICALLY ADDED, SEE BENCHMARKS CRATE -->\n";
 	let readme = std::path::Path::new("../../README.md");
@@ -1,3 +1,5 @@
+use std::io::Write;
+
 pub const FILES: &[&str] = &["endgame", "midgame", "earlygame", "startgame"];
 #[allow(dead_code)]
 pub const MOVES_LEFT: &[usize] = &[20, 30, 40, 50];
@@ -5,3 +7,67 @@ pub const MOVES_LEFT: &[usize] = &[20, 30, 40, 50];
 pub const MAX_DEPTHS: &[u8] = &[10, 15, 20, 25];
 #[allow(dead_code)]
 pub const MAX_GROUP_SIZE: usize = 100;
+
+#[allow(dead_code)]
+pub enum Alignment {
+	Left,
+	Center,
+	Right,
+	Default,
+}
+
+#[allow(dead_code)]
+pub fn generate_table(
+	alignments: [Alignment; 5],
+	headers: [String; 5],
+	body: Vec<[String; 5]>,
+) -> Vec<u8> {
+	let mut buffer = Vec::new();
+	let lengths: [usize; 5] = [headers.clone()]
+		.iter()
+		.chain(body.iter())
+		.map(|[a, b, c, d, e]| [a.len(), b.len(), c.len(), d.len(), e.len()])
+		.fold(
+			[0; 5],
+			|[acc_a, acc_b, acc_c, acc_d, acc_e], [len_a, len_b, len_c, len_d, len_e]| {
+				[
+					acc_a.max(len_a),
+					acc_b.max(len_b),
+					acc_c.max(len_c),
+					acc_d.max(len_d),
+					acc_e.max(len_e),
+				]
+			},
+		);
+	// format!("{:-<l$}", "")
+	let underlines: Vec<String> = lengths
+		.iter()
+		.zip(&alignments)
+		.map(|(l, a)| match a {
+			Alignment::Left => format!(":{:-<width$}", "-", width = l - 1),
+			Alignment::Center => format!(":{:-<width$}:", "-", width = l - 2),
+			Alignment::Right => format!("{:-<width$}:", "-", width = l - 1),
+			Alignment::Default => format!("{:-<width$}", "-", width = l),
+		})
+		.collect();
+
+	[headers]
+		.iter()
+		.chain([underlines.try_into().unwrap()].iter())
+		.chain(body.iter())
+		.zip([lengths].iter().cycle())
+		.zip([&alignments].iter().cycle())
+		.for_each(|((cells, lens), alignments)| {
+			for i in 0..5 {
+				let (cell, len) = (&cells[i], lens[i]);
+				match alignments[i] {
+					Alignment::Left => write!(&mut buffer, "| {cell:<len$} ").ok(),
+					Alignment::Center => write!(&mut buffer, "| {cell:^len$} ").ok(),
+					Alignment::Right => write!(&mut buffer, "| {cell:>len$} ").ok(),
+					Alignment::Default => write!(&mut buffer, "| {cell:len$} ").ok(),
+				};
+			}
+			writeln!(&mut buffer, "|").ok();
+		});
+	buffer
+}
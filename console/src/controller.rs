@@ -0,0 +1,212 @@
+use ascacou::{Board, Engine, Info, Limits, Move, Score, Search};
+use rand::seq::IteratorRandom;
+use std::str::FromStr;
+use std::time::Duration;
+
+/**
+ * Who chooses the moves of a side, parsed from the command line:
+ *
+ * ```text
+ * human
+ * random
+ * minicou[:depth=<1-25>][,time=<ms>]
+ * alphacou[:time=<ms>]
+ * ```
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Controller {
+	Human,
+	Random,
+	Minicou { depth: u8, time: Duration },
+	Alphacou { time: Duration },
+}
+
+/// A move chosen by an engine, with what it thinks of it.
+pub struct EngineMove {
+	pub mov: Move,
+	pub evaluation: Option<String>,
+}
+
+impl Controller {
+	pub fn is_human(&self) -> bool {
+		matches!(self, Controller::Human)
+	}
+
+	/// The move of an engine, `None` for humans or without any
+	/// possible move. Engines search until their time is up or
+	/// `stopped` returns true.
+	pub fn choose(&self, board: &Board, stopped: &dyn Fn() -> bool) -> Option<EngineMove> {
+		let (mut engine, limits): (Box<dyn Engine>, Limits) = match *self {
+			Controller::Human => return None,
+			Controller::Random => {
+				return board
+					.possible_moves()
+					.choose(&mut rand::rng())
+					.map(|mov| EngineMove {
+						mov,
+						evaluation: None,
+					});
+			}
+			Controller::Minicou { depth, time } => (
+				Box::new(minicou::Minicou::default()),
+				Limits {
+					depth: Some(depth),
+					movetime: Some(time),
+					..Limits::default()
+				},
+			),
+			Controller::Alphacou { time } => (
+				Box::new(alphacou::Alphacou::default()),
+				Limits {
+					movetime: Some(time),
+					..Limits::default()
+				},
+			),
+		};
+		let mut last = None;
+		let mut report = |info: Info| last = Some(info);
+		let mov = engine.search(board, &mut Search::new(limits, stopped, &mut report))?;
+		Some(EngineMove {
+			mov,
+			evaluation: last.as_ref().and_then(evaluation),
+		})
+	}
+}
+
+/// What an engine thinks of its move, from the last info it reported.
+fn evaluation(info: &Info) -> Option<String> {
+	match info.score? {
+		Score::Tiles(score) => Some(format!(
+			"score {:+} at depth {}, {} positions",
+			score, info.depth, info.nodes
+		)),
+		Score::Mean(mean) => Some(format!("mean simulation {:+.2}", mean)),
+	}
+}
+
+impl std::fmt::Display for Controller {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Controller::Human => write!(f, "human"),
+			Controller::Random => write!(f, "random"),
+			Controller::Minicou { depth, time } => {
+				write!(f, "minicou (depth {}, {}ms)", depth, time.as_millis())
+			}
+			Controller::Alphacou { time } => write!(f, "alphacou ({}ms)", time.as_millis()),
+		}
+	}
+}
+
+impl FromStr for Controller {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Controller, String> {
+		let (name, options) = input.split_once(':').unwrap_or((input, ""));
+		let mut depth = 8;
+		let mut time = Duration::from_millis(1000);
+		for option in options.split(',').filter(|option| !option.is_empty()) {
+			let (key, value) = option
+				.split_once('=')
+				.ok_or(format!("expected <option>=<value>, got '{}'", option))?;
+			match key {
+				"depth" => {
+					depth = value
+						.parse()
+						.ok()
+						.filter(|depth| (1..=25).contains(depth))
+						.ok_or(format!("depth should be in 1..=25, got '{}'", value))?
+				}
+				"time" => {
+					time = value
+						.parse()
+						.map(Duration::from_millis)
+						.map_err(|_| format!("time should be in milliseconds, got '{}'", value))?
+				}
+				_ => return Err(format!("unknown option '{}'", key)),
+			}
+		}
+		let controller = match name {
+			"human" => Controller::Human,
+			"random" => Controller::Random,
+			"minicou" => Controller::Minicou { depth, time },
+			"alphacou" => Controller::Alphacou { time },
+			_ => {
+				return Err(format!(
+					"unknown player '{}', expected human, random, minicou or alphacou",
+					name
+				));
+			}
+		};
+		let allowed: &[&str] = match controller {
+			Controller::Human | Controller::Random => &[],
+			Controller::Minicou { .. } => &["depth", "time"],
+			Controller::Alphacou { .. } => &["time"],
+		};
+		if let Some(option) = options
+			.split(',')
+			.filter_map(|option| option.split_once('='))
+			.find(|(key, _)| !allowed.contains(key))
+		{
+			return Err(format!("{} has no '{}' option", name, option.0));
+		}
+		Ok(controller)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		assert_eq!("human".parse(), Ok(Controller::Human));
+		assert_eq!(
+			"minicou".parse(),
+			Ok(Controller::Minicou {
+				depth: 8,
+				time: Duration::from_millis(1000)
+			})
+		);
+		assert_eq!(
+			"minicou:depth=12,time=300".parse(),
+			Ok(Controller::Minicou {
+				depth: 12,
+				time: Duration::from_millis(300)
+			})
+		);
+		assert_eq!(
+			"alphacou:time=50".parse(),
+			Ok(Controller::Alphacou {
+				time: Duration::from_millis(50)
+			})
+		);
+		assert!("alphacou:depth=3".parse::<Controller>().is_err());
+		assert!("minicou:depth=30".parse::<Controller>().is_err());
+		assert!("random:time".parse::<Controller>().is_err());
+		assert!("stockfish".parse::<Controller>().is_err());
+	}
+
+	#[test]
+	fn test_choose() {
+		let board = Board::from_fen("bw/ww/b// 01234567").unwrap();
+		assert!(Controller::Human.choose(&board, &|| false).is_none());
+		let chosen = Controller::Random.choose(&board, &|| false).unwrap();
+		assert!(board.is_move_possible(&chosen.mov));
+
+		// Shallow depths are searched as given.
+		let minicou = Controller::Minicou {
+			depth: 1,
+			time: Duration::from_millis(1000),
+		};
+		let chosen = minicou.choose(&board, &|| false).unwrap();
+		assert!(board.is_move_possible(&chosen.mov));
+		assert!(chosen.evaluation.unwrap().contains("at depth 1,"));
+
+		// Stopped engines still have a move.
+		let alphacou = Controller::Alphacou {
+			time: Duration::from_secs(3600),
+		};
+		let chosen = alphacou.choose(&board, &|| true).unwrap();
+		assert!(board.is_move_possible(&chosen.mov));
+	}
+}
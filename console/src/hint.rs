@@ -0,0 +1,131 @@
+use ascacou::{Board, Color, Move};
+
+/// Moves of the current player rated by a shallow minicou search,
+/// to teach which squares are good.
+pub struct Hint {
+	/// Every legal move with its score for the player making it,
+	/// best first.
+	pub moves: Vec<(Move, i16)>,
+	/// The best move followed by the principal variation of its
+	/// search.
+	pub line: Vec<Move>,
+	pub depth: u8,
+}
+
+impl Hint {
+	/// Search the position after each legal move `depth` moves deep,
+	/// `None` when `should_stop` returned true before the end.
+	pub fn new(board: &Board, depth: u8, should_stop: &dyn Fn() -> bool) -> Option<Hint> {
+		let mut searches: Vec<(Move, i16, Vec<Move>)> = board
+			.possible_moves()
+			.map(|mov| {
+				if should_stop() {
+					return None;
+				}
+				let next = board.next(&mov).expect("possible moves are legal");
+				// Scores are for the player to move, the opponent here.
+				match minicou::solve_until(&next, depth, &|_| should_stop()) {
+					Some(solution) => Some((mov, -solution.score, solution.pv)),
+					None if next.is_terminal() => {
+						Some((mov, -(next.current_score() as i16), vec![]))
+					}
+					None => None,
+				}
+			})
+			.collect::<Option<_>>()?;
+		searches.sort_by_key(|&(_, score, _)| -score);
+
+		let line = match searches.first() {
+			Some((best, _, pv)) => [&[*best], pv.as_slice()].concat(),
+			None => vec![],
+		};
+		Some(Hint {
+			moves: searches
+				.into_iter()
+				.map(|(mov, score, _)| (mov, score))
+				.collect(),
+			line,
+			depth,
+		})
+	}
+
+	/// Score of playing `color` at `(x, y)`, `None` when illegal.
+	pub fn score(&self, x: u8, y: u8, color: Color) -> Option<i16> {
+		self.moves
+			.iter()
+			.find(|(mov, _)| *mov == Move::new(x, y, color))
+			.map(|&(_, score)| score)
+	}
+
+	/// Score of the best color on each square, as a grid.
+	pub fn heatmap(&self) -> String {
+		let mut str = String::from("      a    b    c    d    e\n");
+		for y in 0..5 {
+			str.push_str(&format!("{}  ", y + 1));
+			for x in 0..5 {
+				let best = [Color::Black, Color::White]
+					.into_iter()
+					.filter_map(|color| Some((self.score(x, y, color)?, color)))
+					.max_by_key(|&(score, _)| score);
+				match best {
+					Some((score, Color::Black)) => str.push_str(&format!(" {:+3}b", score)),
+					Some((score, Color::White)) => str.push_str(&format!(" {:+3}w", score)),
+					None => str.push_str("    ."),
+				}
+			}
+			str.push('\n');
+		}
+		str
+	}
+
+	/// The best move and the line following it.
+	pub fn summary(&self) -> String {
+		match self.moves.first() {
+			Some((mov, score)) => format!(
+				"Best move {} ({:+} at depth {}), expected line: {}",
+				mov,
+				score,
+				self.depth,
+				self.line
+					.iter()
+					.map(Move::to_string)
+					.collect::<Vec<String>>()
+					.join(" ")
+			),
+			None => "No legal move.".to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hint() {
+		let board = Board::from_fen("bwbw/ww1w/// 01234567").unwrap();
+		assert!(Hint::new(&board, 2, &|| true).is_none());
+		let hint = Hint::new(&board, 2, &|| false).unwrap();
+		assert_eq!(hint.moves.len(), board.possible_moves().count());
+		assert!(hint.moves.is_sorted_by_key(|&(_, score)| -score));
+		assert_eq!(hint.line.first(), hint.moves.first().map(|(mov, _)| mov));
+		assert_eq!(hint.line.len(), 3);
+		let mut next = board;
+		for mov in &hint.line {
+			next.play(*mov).unwrap();
+		}
+		// White on c2 would complete tile 1 again.
+		assert_eq!(hint.score(2, 1, Color::White), None);
+		assert!(hint.score(2, 1, Color::Black).is_some());
+
+		let heatmap = hint.heatmap();
+		assert_eq!(heatmap.lines().count(), 6);
+		assert!(
+			heatmap
+				.lines()
+				.nth(1)
+				.unwrap()
+				.starts_with("1      .    .    .    .")
+		);
+	}
+}
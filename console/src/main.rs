@@ -0,0 +1,249 @@
+mod controller;
+mod hint;
+mod tui;
+
+use ascacou::{Board, Game, Move, PlayerInfo, Setup, Side};
+use clap::Parser;
+use controller::Controller;
+use hint::Hint;
+use std::io::IsTerminal;
+use std::thread;
+use std::time::Duration;
+
+/// Let both players pick their tiles in turn.
+fn draft() -> Board {
+	let mut setup = Setup::draft();
+	while let Some(side) = setup.to_pick() {
+		let side = match side {
+			Side::First => "First",
+			Side::Second => "Second",
+		};
+		println!("Available tiles: {}", setup.available());
+		println!("{} player, pick a tile (0-f):", side);
+		let mut input = String::new();
+		if std::io::stdin().read_line(&mut input).is_err() {
+			continue;
+		}
+		match u8::from_str_radix(input.trim(), 16) {
+			Ok(tile) => {
+				if let Err(error) = setup.pick(tile) {
+					println!("{}", error);
+				}
+			}
+			Err(_) => println!("Not a tile: {}", input.trim()),
+		}
+	}
+	setup.board().expect("draft is complete")
+}
+
+enum Action {
+	Move(Move),
+	Undo,
+	Redo,
+	Hint,
+	Quit,
+}
+
+fn read_action(game: &Game) -> Action {
+	loop {
+		let mut input = String::new();
+		match std::io::stdin().read_line(&mut input) {
+			Ok(0) => return Action::Quit,
+			Ok(_) => (),
+			Err(_) => continue,
+		}
+		let input = input.trim().to_lowercase();
+		match input.as_str() {
+			"r" | "rewind" | "u" | "undo" => return Action::Undo,
+			"redo" => return Action::Redo,
+			"h" | "hint" => return Action::Hint,
+			"q" | "quit" => return Action::Quit,
+			_ => match Move::try_from(input.as_str()) {
+				Ok(mov) => match game.board().apply(&mov) {
+					Ok(_) => return Action::Move(mov),
+					Err(error) => println!("{}", error),
+				},
+				Err(error) => println!("{}", error),
+			},
+		}
+	}
+}
+
+fn side_name(side: Side) -> &'static str {
+	match side {
+		Side::First => "First",
+		Side::Second => "Second",
+	}
+}
+
+fn run(mut game: Game, controllers: [Controller; 2], delay: Duration, hint_depth: u8) {
+	let watching = controllers.iter().all(|controller| !controller.is_human());
+	// What the last engine move was or a hint, shown until the next
+	// move.
+	let mut message = None;
+	loop {
+		println!("\x1bc\x1b[3J"); /* clear screen */
+		println!("{}", game.board().for_console());
+		for side in [Side::First, Side::Second] {
+			println!("{} player: {}", side_name(side), game.player(side).name);
+		}
+		if let Some(message) = &message {
+			println!("{}", message);
+		}
+
+		if let Some(result) = game.result() {
+			println!("Game over, {}!", result);
+			break;
+		}
+		let board = game.board();
+		let bounds = board.score_bounds();
+		println!(
+			"Final score between {} and {}{}",
+			bounds.start(),
+			bounds.end(),
+			if board.is_decided() {
+				", the game is decided."
+			} else {
+				"."
+			}
+		);
+
+		let side = game.side();
+		let controller = controllers[side as usize];
+		if !controller.is_human() {
+			println!("{} is thinking...", controller);
+			let Some(chosen) = controller.choose(board, &|| false) else {
+				println!("{} found no move.", controller);
+				break;
+			};
+			message = Some(match chosen.evaluation {
+				Some(evaluation) => format!(
+					"{} player played {} ({})",
+					side_name(side),
+					chosen.mov,
+					evaluation
+				),
+				None => format!("{} player played {}", side_name(side), chosen.mov),
+			});
+			game.play(chosen.mov).expect("engines play legal moves");
+			if watching {
+				thread::sleep(delay);
+			}
+			continue;
+		}
+
+		println!(
+			"Possible moves: {}",
+			board
+				.possible_moves()
+				.map(String::from)
+				.collect::<Vec<String>>()
+				.join(", ")
+		);
+		println!("\nYour move (ba1 = black to the first row/col), [u]ndo, redo, [h]int or [q]uit:");
+
+		match read_action(&game) {
+			Action::Move(mov) => {
+				game.play(mov).expect("move was checked");
+				message = None;
+			}
+			// Take back or replay engine moves as well, up to the next
+			// human move.
+			Action::Undo => {
+				game.undo();
+				while !game.player(game.side()).human && game.undo().is_some() {}
+				message = None;
+			}
+			Action::Redo => {
+				game.redo();
+				while !game.player(game.side()).human && game.redo().is_some() {}
+				message = None;
+			}
+			Action::Hint => {
+				let hint = Hint::new(game.board(), hint_depth, &|| false)
+					.expect("hints are never stopped here");
+				message = Some(format!("{}{}", hint.heatmap(), hint.summary()));
+			}
+			Action::Quit => break,
+		}
+	}
+}
+
+/// Parse a FEN and check that it describes a possible position.
+fn parse_board(fen: &str) -> Result<Board, String> {
+	let board = Board::from_fen(fen).map_err(|error| error.to_string())?;
+	board.validate().map_err(|violations| {
+		format!(
+			"invalid board: {}",
+			violations
+				.iter()
+				.map(|violation| violation.to_string())
+				.collect::<Vec<String>>()
+				.join(", ")
+		)
+	})?;
+	Ok(board)
+}
+
+#[derive(Parser)]
+#[clap(about, author, version)]
+struct Args {
+	/// Board in FEN format, an empty board by default
+	#[clap(value_parser = parse_board)]
+	board: Option<Board>,
+
+	/// Let both players pick their tiles in turn
+	#[clap(long, conflicts_with = "board")]
+	draft: bool,
+
+	/// First player: human, random, minicou[:depth=<1-25>][,time=<ms>]
+	/// or alphacou[:time=<ms>]
+	#[clap(long, default_value = "human")]
+	first: Controller,
+
+	/// Second player, see --first
+	#[clap(long, default_value = "human")]
+	second: Controller,
+
+	/// Pause after each move when engines play each other, in
+	/// milliseconds
+	#[clap(long, default_value_t = 1000)]
+	delay: u64,
+
+	/// Depth of the minicou search rating each move for hints
+	#[clap(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..=25))]
+	hint_depth: u8,
+
+	/// Type moves line by line instead of the full screen interface,
+	/// the default when not in a terminal
+	#[clap(long)]
+	plain: bool,
+}
+
+fn main() {
+	let args = Args::parse();
+
+	let board = if args.draft {
+		draft()
+	} else {
+		args.board.unwrap_or(Board::empty())
+	};
+	let controllers = [args.first, args.second];
+	let mut game = Game::new(board);
+	for (side, controller) in [Side::First, Side::Second].into_iter().zip(controllers) {
+		game.set_player(
+			side,
+			PlayerInfo {
+				name: controller.to_string(),
+				human: controller.is_human(),
+			},
+		);
+	}
+	let delay = Duration::from_millis(args.delay);
+	if args.plain || !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+		run(game, controllers, delay, args.hint_depth);
+	} else if let Err(error) = tui::run(game, controllers, delay, args.hint_depth) {
+		eprintln!("Terminal error: {}", error);
+		std::process::exit(1);
+	}
+}
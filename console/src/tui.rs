@@ -0,0 +1,516 @@
+// Full screen game, squares being picked with the arrow keys.
+//
+//   First player: human                Moves
+//   Second player: minicou (...)        1. ba1 wb2
+//                                       2. ...
+//   <opponent's rack>
+//                                      Score: ...
+//   <board>                            Reachable tiles: ...
+//
+//   <current player's rack>
+//   <message>
+//   <keys>
+
+use crate::controller::Controller;
+use crate::hint::Hint;
+use crate::side_name;
+use ascacou::{Ansi, Board, Color, Game, Move, Renderer, Side};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{
+	Color as TermColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::cell::Cell;
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+const PANEL_COLUMN: u16 = 50;
+const BOARD_COLUMN: u16 = 17;
+const BOARD_ROW: u16 = 7;
+
+const KEYS: &str = "arrows: square  space: color  enter: play  h: hint  u: undo  r: redo  q: quit";
+
+/// Raw mode and alternate screen, restored when dropped, panics
+/// included.
+struct Screen {
+	out: Stdout,
+}
+
+impl Screen {
+	fn enter() -> io::Result<Screen> {
+		let mut out = io::stdout();
+		terminal::enable_raw_mode()?;
+		execute!(out, EnterAlternateScreen, Hide)?;
+		Ok(Screen { out })
+	}
+}
+
+impl Drop for Screen {
+	fn drop(&mut self) {
+		let _ = execute!(self.out, Show, LeaveAlternateScreen);
+		let _ = terminal::disable_raw_mode();
+	}
+}
+
+struct Tui {
+	game: Game,
+	controllers: [Controller; 2],
+	cursor: (u8, u8),
+	color: Color,
+	/// Why a move was rejected, or what an engine played.
+	message: String,
+	hint_depth: u8,
+	/// Hint shown while its position is on the board.
+	hint: Option<(Board, Hint)>,
+}
+
+enum Input {
+	Key(KeyCode),
+	Quit,
+}
+
+fn is_quit(code: KeyCode, modifiers: KeyModifiers) -> bool {
+	match code {
+		KeyCode::Char('c') => modifiers.contains(KeyModifiers::CONTROL),
+		KeyCode::Char('q') | KeyCode::Esc => true,
+		_ => false,
+	}
+}
+
+fn read_input() -> io::Result<Input> {
+	loop {
+		if let Event::Key(KeyEvent {
+			code,
+			modifiers,
+			kind: KeyEventKind::Press,
+			..
+		}) = event::read()?
+		{
+			return Ok(if is_quit(code, modifiers) {
+				Input::Quit
+			} else {
+				Input::Key(code)
+			});
+		}
+	}
+}
+
+/// Whether a quit key was pressed within `delay`, other keys being
+/// dropped.
+fn quit_within(delay: Duration) -> io::Result<bool> {
+	let deadline = std::time::Instant::now() + delay;
+	loop {
+		let left = deadline.saturating_duration_since(std::time::Instant::now());
+		if !event::poll(left)? {
+			return Ok(false);
+		}
+		if let Event::Key(KeyEvent {
+			code,
+			modifiers,
+			kind: KeyEventKind::Press,
+			..
+		}) = event::read()?
+			&& is_quit(code, modifiers)
+		{
+			return Ok(true);
+		}
+	}
+}
+
+/// Score and score bounds of the first player.
+fn first_player_score(board: &Board) -> (i8, i8, i8) {
+	let score = board.current_score();
+	let bounds = board.score_bounds();
+	match board.side() {
+		Side::First => (score, *bounds.start(), *bounds.end()),
+		Side::Second => (-score, -bounds.end(), -bounds.start()),
+	}
+}
+
+/// One line per move of both players, numbered from `first_ply`, the
+/// ply of the first of `moves`. A game starting with a move of the
+/// second player has its first line start with `...`.
+fn numbered_moves(first_ply: usize, moves: &[Move]) -> Vec<String> {
+	let mut lines: Vec<String> = vec![];
+	for (index, mov) in moves.iter().enumerate() {
+		let ply = first_ply + index;
+		match lines.last_mut() {
+			Some(line) if !ply.is_multiple_of(2) => line.push_str(&format!(" {}", mov)),
+			_ => lines.push(format!(
+				"{: >3}. {}{}",
+				ply / 2 + 1,
+				if ply.is_multiple_of(2) { "" } else { "... " },
+				mov
+			)),
+		}
+	}
+	lines
+}
+
+fn piece_color(color: Color) -> TermColor {
+	// Same colors as the `Ansi` renderer.
+	match color {
+		Color::Black => TermColor::DarkRed,
+		Color::White => TermColor::Black,
+	}
+}
+
+impl Tui {
+	fn mov(&self) -> Move {
+		Move::new(self.cursor.0, self.cursor.1, self.color)
+	}
+
+	fn hint(&self) -> Option<&Hint> {
+		match &self.hint {
+			Some((board, hint)) if board == self.game.board() => Some(hint),
+			_ => None,
+		}
+	}
+
+	fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+		let board = self.game.board();
+		queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+		for (row, side) in [Side::First, Side::Second].into_iter().enumerate() {
+			let turn = if side == board.side() && !self.game.is_over() {
+				"> "
+			} else {
+				"  "
+			};
+			queue!(
+				out,
+				MoveTo(0, row as u16),
+				Print(format!(
+					"{}{} player: {}",
+					turn,
+					side_name(side),
+					self.game.player(side).name
+				))
+			)?;
+		}
+
+		for (row, player) in [
+			(3, board.opponent()),
+			(BOARD_ROW + 7, &board.current_player),
+		] {
+			for (index, line) in Ansi.rack(player, board.played_tiles).lines().enumerate() {
+				queue!(out, MoveTo(0, row + index as u16), Print(line))?;
+			}
+		}
+		self.draw_board(out, board)?;
+
+		self.draw_panel(out, board)?;
+
+		queue!(
+			out,
+			MoveTo(0, BOARD_ROW + 11),
+			Print(&self.message),
+			MoveTo(0, BOARD_ROW + 12),
+			Print(KEYS)
+		)?;
+		out.flush()
+	}
+
+	fn draw_board(&self, out: &mut impl Write, board: &Board) -> io::Result<()> {
+		let (black_legal, white_legal) = board.legal_moves();
+		queue!(out, MoveTo(BOARD_COLUMN, BOARD_ROW), Print("   a b c d e"))?;
+		for y in 0..5 {
+			queue!(
+				out,
+				MoveTo(BOARD_COLUMN, BOARD_ROW + 1 + y as u16),
+				Print(format!("{} ", y + 1))
+			)?;
+			for x in 0..5 {
+				let position = Move::mask_at(x, y);
+				let legal = match self.color {
+					Color::Black => black_legal,
+					Color::White => white_legal,
+				} & position != 0;
+				let is_cursor = self.cursor == (x, y) && !self.game.is_over();
+				// Hints color squares by the score of the selected color.
+				let score = self.hint().and_then(|hint| hint.score(x, y, self.color));
+				let background = match (is_cursor, legal, score) {
+					(false, _, None) => TermColor::Grey,
+					(false, _, Some(score)) if score > 0 => TermColor::Green,
+					(false, _, Some(0)) => TermColor::Yellow,
+					(false, _, Some(_)) => TermColor::Magenta,
+					(true, true, _) => TermColor::DarkGreen,
+					(true, false, _) => TermColor::DarkRed,
+				};
+				let (foreground, glyph) = match board.color_at(x, y) {
+					Some(color) => (piece_color(color), '●'),
+					None if is_cursor => (piece_color(self.color), '○'),
+					None => (
+						TermColor::Black,
+						match (black_legal & position != 0, white_legal & position != 0) {
+							(true, true) => '·',
+							(true, false) => 'b',
+							(false, true) => 'w',
+							(false, false) => 'x',
+						},
+					),
+				};
+				queue!(
+					out,
+					SetBackgroundColor(TermColor::Grey),
+					Print(' '),
+					SetBackgroundColor(background),
+					SetForegroundColor(foreground),
+					Print(glyph),
+				)?;
+			}
+			queue!(
+				out,
+				SetBackgroundColor(TermColor::Grey),
+				Print(' '),
+				ResetColor
+			)?;
+		}
+		Ok(())
+	}
+
+	fn draw_panel(&self, out: &mut impl Write, board: &Board) -> io::Result<()> {
+		let mut lines = vec![];
+		let (score, min, max) = first_player_score(board);
+		match self.game.result() {
+			Some(result) => lines.push(format!("Game over, {}!", result)),
+			None => {
+				lines.push(format!("Score: {:+} (first player)", score));
+				lines.push(format!("Final score: {:+} to {:+}", min, max));
+			}
+		}
+		let reachable = board.reachable_tiles();
+		lines.push("Reachable tiles:".to_string());
+		for (side, player) in [
+			(board.side(), &board.current_player),
+			(!board.side(), board.opponent()),
+		] {
+			lines.push(format!(
+				"  {}: {}",
+				side_name(side),
+				player.tiles() & reachable
+			));
+		}
+		if let Some(hint) = self.hint() {
+			lines.push(String::new());
+			lines.push(format!("Hint, depth {}:", hint.depth));
+			if let Some(score) = hint.score(self.cursor.0, self.cursor.1, self.color) {
+				lines.push(format!("  {} scores {:+}", self.mov(), score));
+			}
+			for (mov, score) in hint.moves.iter().take(3) {
+				lines.push(format!("  {} {:+}", mov, score));
+			}
+			lines.push(format!(
+				"  line: {}",
+				hint.line
+					.iter()
+					.map(Move::to_string)
+					.collect::<Vec<String>>()
+					.join(" ")
+			));
+		}
+		lines.push(String::new());
+		lines.push("Moves".to_string());
+		lines.extend(numbered_moves(
+			self.game.initial_board().ply() as usize,
+			self.game.moves(),
+		));
+		if self.game.can_redo() {
+			lines.push(format!(
+				"     redo: {}",
+				self.game
+					.undone_moves()
+					.map(Move::to_string)
+					.collect::<Vec<String>>()
+					.join(" ")
+			));
+		}
+		for (row, line) in lines.iter().enumerate() {
+			queue!(out, MoveTo(PANEL_COLUMN, row as u16), Print(line))?;
+		}
+		Ok(())
+	}
+
+	/// Handle a key of a human player. Hints are searched until
+	/// `stopped` returns true.
+	fn handle_key(&mut self, code: KeyCode, stopped: &dyn Fn() -> bool) {
+		let (x, y) = self.cursor;
+		match code {
+			KeyCode::Left => self.cursor = ((x + 4) % 5, y),
+			KeyCode::Right => self.cursor = ((x + 1) % 5, y),
+			KeyCode::Up => self.cursor = (x, (y + 4) % 5),
+			KeyCode::Down => self.cursor = (x, (y + 1) % 5),
+			KeyCode::Char(' ') | KeyCode::Tab | KeyCode::Char('c') => self.color = !self.color,
+			KeyCode::Char('b') => self.color = Color::Black,
+			KeyCode::Char('w') => self.color = Color::White,
+			KeyCode::Enter => {
+				let mov = self.mov();
+				self.message = match self.game.play(mov) {
+					Ok(_) => String::new(),
+					Err(error) => format!("Rejected: {}", error),
+				};
+			}
+			// Take back or replay engine moves as well, up to the next
+			// human move.
+			KeyCode::Char('u') => {
+				self.game.undo();
+				while !self.game.player(self.game.side()).human && self.game.undo().is_some() {}
+				self.message = String::new();
+			}
+			KeyCode::Char('r') => {
+				self.game.redo();
+				while !self.game.player(self.game.side()).human && self.game.redo().is_some() {}
+				self.message = String::new();
+			}
+			KeyCode::Char('h') if self.hint().is_some() => self.hint = None,
+			KeyCode::Char('h') => {
+				let board = *self.game.board();
+				self.hint = Hint::new(&board, self.hint_depth, stopped).map(|hint| (board, hint));
+			}
+			_ => (),
+		}
+	}
+}
+
+/// Play `game` full screen until it is over or someone quits.
+pub fn run(
+	game: Game,
+	controllers: [Controller; 2],
+	delay: Duration,
+	hint_depth: u8,
+) -> io::Result<()> {
+	let mut screen = Screen::enter()?;
+	let watching = controllers.iter().all(|controller| !controller.is_human());
+	let mut tui = Tui {
+		game,
+		controllers,
+		cursor: (2, 2),
+		color: Color::Black,
+		message: String::new(),
+		hint_depth,
+		hint: None,
+	};
+	// Searches poll the keyboard to be stopped by a quit key, once
+	// pressed it stays so.
+	let quit = Cell::new(false);
+	let stopped = || {
+		if !quit.get() {
+			quit.set(quit_within(Duration::ZERO).is_ok_and(|quit| quit));
+		}
+		quit.get()
+	};
+	loop {
+		tui.draw(&mut screen.out)?;
+		let side = tui.game.side();
+		let controller = tui.controllers[side as usize];
+		if tui.game.is_over() || controller.is_human() {
+			match read_input()? {
+				Input::Quit => return Ok(()),
+				// Undo is still possible once the game is over.
+				Input::Key(code) => tui.handle_key(code, &stopped),
+			}
+			if quit.get() {
+				return Ok(());
+			}
+			continue;
+		}
+
+		tui.message = format!("{} is thinking...", controller);
+		tui.draw(&mut screen.out)?;
+		let chosen = controller.choose(tui.game.board(), &stopped);
+		if quit.get() {
+			return Ok(());
+		}
+		let Some(chosen) = chosen else {
+			tui.message = format!("{} found no move.", controller);
+			tui.draw(&mut screen.out)?;
+			read_input()?;
+			return Ok(());
+		};
+		tui.game.play(chosen.mov).expect("engines play legal moves");
+		tui.message = match chosen.evaluation {
+			Some(evaluation) => format!(
+				"{} player played {} ({})",
+				side_name(side),
+				chosen.mov,
+				evaluation
+			),
+			None => format!("{} player played {}", side_name(side), chosen.mov),
+		};
+		if watching && !tui.game.is_over() {
+			tui.draw(&mut screen.out)?;
+			if quit_within(delay)? {
+				return Ok(());
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tui() -> Tui {
+		Tui {
+			game: Game::new(Board::empty()),
+			controllers: [Controller::Human; 2],
+			cursor: (0, 0),
+			color: Color::Black,
+			message: String::new(),
+			hint_depth: 2,
+			hint: None,
+		}
+	}
+
+	#[test]
+	fn test_keys() {
+		let mut tui = tui();
+		tui.handle_key(KeyCode::Left, &|| false);
+		tui.handle_key(KeyCode::Up, &|| false);
+		assert_eq!(tui.cursor, (4, 4));
+		tui.handle_key(KeyCode::Char(' '), &|| false);
+		assert_eq!(tui.mov(), Move::white(4, 4));
+		tui.handle_key(KeyCode::Enter, &|| false);
+		assert_eq!(tui.game.moves(), &[Move::white(4, 4)]);
+		tui.handle_key(KeyCode::Enter, &|| false);
+		assert_eq!(
+			tui.message,
+			"Rejected: illegal move we5: square is occupied"
+		);
+		tui.handle_key(KeyCode::Char('u'), &|| false);
+		assert!(tui.game.moves().is_empty());
+		assert!(tui.message.is_empty());
+	}
+
+	#[test]
+	fn test_draw() {
+		let mut tui = tui();
+		tui.handle_key(KeyCode::Enter, &|| false);
+		let mut out = Vec::new();
+		tui.draw(&mut out).unwrap();
+		let out = String::from_utf8(out).unwrap();
+		assert!(out.contains("  1. ba1"));
+		assert!(out.contains("Final score: -8 to +8"));
+	}
+
+	#[test]
+	fn test_numbered_moves() {
+		let moves = [Move::black(0, 0), Move::white(1, 1), Move::black(2, 2)];
+		assert_eq!(numbered_moves(0, &moves), ["  1. ba1 wb2", "  2. bc3"]);
+		assert_eq!(numbered_moves(3, &moves), ["  2. ... ba1", "  3. wb2 bc3"]);
+		assert!(numbered_moves(4, &[]).is_empty());
+	}
+
+	#[test]
+	fn test_hint() {
+		let mut tui = tui();
+		tui.handle_key(KeyCode::Char('h'), &|| false);
+		assert_eq!(tui.hint().unwrap().moves.len(), 50);
+		tui.handle_key(KeyCode::Enter, &|| false);
+		assert!(tui.hint().is_none());
+		tui.handle_key(KeyCode::Char('h'), &|| false);
+		tui.handle_key(KeyCode::Char('h'), &|| false);
+		assert!(tui.hint().is_none());
+	}
+}
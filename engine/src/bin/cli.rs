@@ -0,0 +1,307 @@
+use ascacou::{Board, Game, GameRecord, Move, RENDERER_NAMES, Setup, renderer};
+use clap::{Parser, Subcommand};
+use serde_json::{Value, json};
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+/// Command line interface to the engine, for scripts.
+///
+/// Exit codes:
+///
+/// - 0 on success, a valid board for `validate`, a finished game for
+///   `terminal`;
+/// - 1 for an invalid board with `validate` or an ongoing game with
+///   `terminal`;
+/// - 2 for input that cannot be parsed or describes an invalid board;
+/// - 3 for an illegal move given to `play` or `replay`.
+#[derive(Parser)]
+#[clap(about, author, version, verbatim_doc_comment)]
+struct Args {
+	/// Print results as JSON
+	#[clap(long, global = true)]
+	json: bool,
+
+	#[clap(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Print an empty board with a random tile distribution
+	Random {
+		/// Seed of the distribution, the same seed giving the same board
+		#[clap(long)]
+		seed: Option<u64>,
+	},
+	/// Check that a board can happen in a game
+	Validate { fen: String },
+	/// Play moves in turn and print the resulting board
+	Play { fen: String, moves: Vec<String> },
+	/// List legal moves
+	Moves { fen: String },
+	/// Print the score of the player to move
+	Score { fen: String },
+	/// Print whether the game is over
+	Terminal { fen: String },
+	/// Draw a board
+	Render {
+		fen: String,
+		#[clap(long, default_value = "ansi", value_parser = RENDERER_NAMES)]
+		format: String,
+	},
+	/// Print every position of a game record, e.g. "[01234567] c2 B3"
+	Replay { record: String },
+}
+
+/// What a command prints, as text and as JSON.
+struct Output {
+	text: String,
+	json: Value,
+	/// Whether the exit code is 0 rather than 1.
+	success: bool,
+}
+
+impl Output {
+	fn new(text: String, json: Value) -> Output {
+		Output {
+			text,
+			json,
+			success: true,
+		}
+	}
+}
+
+struct Failure {
+	code: u8,
+	message: String,
+}
+
+impl Failure {
+	fn input(message: impl ToString) -> Failure {
+		Failure {
+			code: 2,
+			message: message.to_string(),
+		}
+	}
+
+	fn illegal(message: impl ToString) -> Failure {
+		Failure {
+			code: 3,
+			message: message.to_string(),
+		}
+	}
+}
+
+fn parse_board(fen: &str) -> Result<Board, Failure> {
+	let board = Board::from_fen(fen).map_err(Failure::input)?;
+	board.validate().map_err(|violations| {
+		Failure::input(format!(
+			"invalid board: {}",
+			violations
+				.iter()
+				.map(|violation| violation.to_string())
+				.collect::<Vec<String>>()
+				.join(", ")
+		))
+	})?;
+	Ok(board)
+}
+
+fn run(command: Command) -> Result<Output, Failure> {
+	match command {
+		Command::Random { seed } => {
+			let seed = seed.unwrap_or_else(rand::random);
+			let board = Setup::random(seed)
+				.board()
+				.expect("random setups are complete");
+			Ok(Output::new(
+				board.fen(),
+				json!({ "fen": board, "seed": seed }),
+			))
+		}
+		Command::Validate { fen } => {
+			let board = Board::from_fen(&fen).map_err(Failure::input)?;
+			let violations: Vec<String> = match board.validate() {
+				Ok(()) => vec![],
+				Err(violations) => violations.iter().map(|v| v.to_string()).collect(),
+			};
+			Ok(Output {
+				text: if violations.is_empty() {
+					"valid".to_string()
+				} else {
+					violations.join("\n")
+				},
+				success: violations.is_empty(),
+				json: json!({ "valid": violations.is_empty(), "violations": violations }),
+			})
+		}
+		Command::Play { fen, moves } => {
+			let mut game = Game::new(parse_board(&fen)?);
+			let mut outcomes = vec![];
+			for mov in moves {
+				let mov = Move::try_from(mov.as_str()).map_err(Failure::input)?;
+				let outcome = game.play(mov).map_err(Failure::illegal)?;
+				outcomes.push(json!({
+					"move": outcome.mov,
+					"tiles": outcome
+						.tiles
+						.iter()
+						.map(|(tile, side)| json!({ "tile": tile, "side": side }))
+						.collect::<Vec<Value>>(),
+					"score_delta": outcome.score_delta,
+				}));
+			}
+			let board = game.board();
+			Ok(Output::new(
+				board.fen(),
+				json!({
+					"fen": board,
+					"moves": outcomes,
+					"terminal": board.is_terminal(),
+				}),
+			))
+		}
+		Command::Moves { fen } => {
+			let moves: Vec<Move> = parse_board(&fen)?.possible_moves().collect();
+			Ok(Output::new(
+				moves
+					.iter()
+					.map(Move::to_string)
+					.collect::<Vec<String>>()
+					.join("\n"),
+				json!(moves),
+			))
+		}
+		Command::Score { fen } => {
+			let board = parse_board(&fen)?;
+			let bounds = board.score_bounds();
+			Ok(Output::new(
+				board.current_score().to_string(),
+				json!({
+					"score": board.current_score(),
+					"side": board.side(),
+					"bounds": [bounds.start(), bounds.end()],
+					"terminal": board.is_terminal(),
+					"decided": board.is_decided(),
+				}),
+			))
+		}
+		Command::Terminal { fen } => {
+			let terminal = parse_board(&fen)?.is_terminal();
+			Ok(Output {
+				text: terminal.to_string(),
+				json: json!(terminal),
+				success: terminal,
+			})
+		}
+		Command::Render { fen, format } => {
+			let board = parse_board(&fen)?;
+			let rendered = renderer(&format)
+				.expect("formats are checked by clap")
+				.board(&board);
+			Ok(Output::new(
+				rendered.trim_end().to_string(),
+				json!({ "format": format, "output": rendered }),
+			))
+		}
+		Command::Replay { record } => {
+			let record = GameRecord::parse(&record).map_err(Failure::input)?;
+			let game = Game::from_record(&record).map_err(Failure::illegal)?;
+			let positions: Vec<Value> = game
+				.boards()
+				.iter()
+				.enumerate()
+				.map(|(index, board)| {
+					json!({
+						"move": index.checked_sub(1).map(|index| game.moves()[index]),
+						"fen": board,
+					})
+				})
+				.collect();
+			Ok(Output::new(
+				game.boards()
+					.iter()
+					.map(Board::fen)
+					.collect::<Vec<String>>()
+					.join("\n"),
+				json!({
+					"positions": positions,
+					"result": game.result().map(|result| result.score),
+				}),
+			))
+		}
+	}
+}
+
+fn main() -> ExitCode {
+	let args = Args::parse();
+	// Output may be cut short by a closed pipe, as with `| head`.
+	let mut stdout = io::stdout();
+	match run(args.command) {
+		Ok(output) => {
+			let _ = if args.json {
+				writeln!(stdout, "{}", output.json)
+			} else {
+				writeln!(stdout, "{}", output.text)
+			};
+			if output.success {
+				ExitCode::SUCCESS
+			} else {
+				ExitCode::FAILURE
+			}
+		}
+		Err(failure) => {
+			if args.json {
+				let _ = writeln!(stdout, "{}", json!({ "error": failure.message }));
+			}
+			eprintln!("{}", failure.message);
+			ExitCode::from(failure.code)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn run_args(args: &[&str]) -> Result<Output, Failure> {
+		let args = Args::try_parse_from([&["ascacou"], args].concat()).unwrap();
+		run(args.command)
+	}
+
+	#[test]
+	fn test_commands() {
+		let random = run_args(&["random", "--seed", "1"]).ok().unwrap();
+		assert_eq!(
+			random.text,
+			run_args(&["random", "--seed", "1"]).ok().unwrap().text
+		);
+
+		let played = run_args(&["play", "//// 01234567", "ba1", "wb1"])
+			.ok()
+			.unwrap();
+		assert_eq!(played.text, "bw//// 01234567");
+		assert_eq!(played.json["moves"][1]["move"], "wb1");
+
+		let terminal = run_args(&["terminal", "//// 01234567"]).ok().unwrap();
+		assert_eq!((terminal.text.as_str(), terminal.success), ("false", false));
+
+		let replay = run_args(&["replay", "[01234567] c2 B3"]).ok().unwrap();
+		assert_eq!(replay.text.lines().count(), 3);
+		assert_eq!(replay.json["positions"][0]["move"], Value::Null);
+	}
+
+	#[test]
+	fn test_exit_codes() {
+		let code = |args: &[&str]| match run_args(args) {
+			Ok(output) => u8::from(!output.success),
+			Err(failure) => failure.code,
+		};
+		assert_eq!(code(&["validate", "bwbw/ww1w/// 01234567"]), 0);
+		assert_eq!(code(&["validate", "bwbw/ww1w/bwbw/ww1w/ 01234567"]), 1);
+		assert_eq!(code(&["score", "bwbw/ww1w/bwbw/ww1w/ 01234567"]), 2);
+		assert_eq!(code(&["moves", "6/ 01234567"]), 2);
+		assert_eq!(code(&["play", "//// 01234567", "ba1", "wa1"]), 3);
+		assert_eq!(code(&["replay", "[01234567] c2 C2"]), 3);
+	}
+}
@@ -1,44 +1,102 @@
 use rand::Rng;
 
+use crate::codec::{self, DecodeError};
 use crate::color::Color;
+use crate::error::{IllegalMove, IllegalMoveKind, ParseError, ParseErrorKind::*, Violation};
 use crate::mov::Move;
-use crate::player::Player;
+use crate::movegen;
+use crate::outcome::MoveOutcome;
+use crate::player::{Player, Side};
+use crate::render::{Ansi, Renderer};
+use crate::symmetry::Symmetry;
 use crate::tileset::TileSet;
-use std::iter::FromIterator;
+use crate::window::Window;
+use crate::zobrist;
+use std::ops::RangeInclusive;
+
+/// Every square of the 5x5 area in a 7x7 mask, see `Move::mask_at`.
+pub(crate) const BOARD_MASK: u64 = 0b0000000_0111110_0111110_0111110_0111110_0111110_0000000u64;
 
 // TODO: rip it off!!!!
-#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub struct Board {
 	pub pieces_mask: u64,
 	pub black_mask: u64,
 	pub current_player: Player,
 	opponent: Player,
 	pub played_tiles: TileSet,
+	/// Zobrist hash, updated on each move. It is only valid as long
+	/// as public fields are not changed by hand.
+	hash: u64,
+	/// Whether the current player is the one who moved first.
+	side: Side,
+	/// Number of moves played since the start of the game.
+	ply: u8,
+}
+
+/// Everything needed to take back a move, see `Board::play`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Undo {
+	mask: u64,
+	played_tiles: TileSet,
 }
 
 impl Board {
-	pub fn empty() -> Board {
-		let (current_player, opponent) = Player::default_set();
+	/// Build a board from its pieces, computing played tiles and hash.
+	/// Every piece is considered to be a move played since the start
+	/// of the game.
+	fn with_players(pieces_mask: u64, black_mask: u64, players: (Player, Player)) -> Board {
+		let (current_player, opponent) = players;
+		let ply = pieces_mask.count_ones() as u8;
 		Board {
-			pieces_mask: 0,
-			black_mask: 0,
+			pieces_mask,
+			black_mask,
 			current_player,
 			opponent,
-			played_tiles: TileSet::empty(),
+			played_tiles: TileSet::union_of(filled_tiles(pieces_mask, black_mask)),
+			hash: zobrist::hash(pieces_mask, black_mask, current_player.tiles()),
+			side: Side::from_ply(ply),
+			ply,
 		}
 	}
 
-	pub fn random_empty<R: Rng>(rng: &mut R) -> Board {
-		let (current_player, opponent) = Player::random_set(rng);
+	/// Build a board from an external representation, `tiles` being
+	/// the tiles of the current player. It is not validated here, as
+	/// `decode` reports invalid boards and FENs may describe them.
+	pub(crate) fn from_parts(
+		pieces_mask: u64,
+		black_mask: u64,
+		tiles: TileSet,
+		side: Side,
+		ply: u8,
+	) -> Board {
 		Board {
-			pieces_mask: 0,
-			black_mask: 0,
-			current_player,
-			opponent,
-			played_tiles: TileSet::empty(),
+			side,
+			ply,
+			..Board::with_players(pieces_mask, black_mask, Player::pair_unchecked(tiles))
 		}
 	}
 
+	pub fn empty() -> Board {
+		Board::with_players(0, 0, Player::default_set())
+	}
+
+	/// An empty board where the current player owns `tiles`,
+	/// and their opponent owns every other tile.
+	pub(crate) fn empty_with_tiles(tiles: TileSet) -> Board {
+		Board::with_players(0, 0, Player::pair_unchecked(tiles))
+	}
+
+	/// An empty board for a custom distribution, `tiles` being the
+	/// tiles of the first player.
+	pub fn empty_with_distribution(tiles: TileSet) -> Result<Board, Violation> {
+		Ok(Board::with_players(0, 0, Player::pair(tiles)?))
+	}
+
+	pub fn random_empty<R: Rng>(rng: &mut R) -> Board {
+		Board::with_players(0, 0, Player::random_set(rng))
+	}
+
 	/**
 	 * Any Ascacou position may quite simply be described by a PGN
 	 * like notation. For instance:
@@ -54,7 +112,7 @@ impl Board {
 	 *
 	 * Could be refered to as `c2 B3 B1 C3 c1 D1`. This strings
 	 * gives us the information of move order, and the case tells
-	 * the color (white is upper cased).
+	 * the color (white is upper cased). See `GameRecord`.
 	 *
 	 * If we don't care about move order we can also have a FEN
 	 * like reference: `1wbw/2b/1ww/5/5`. Note that we are starting
@@ -69,6 +127,13 @@ impl Board {
 	 *
 	 * So a full FEN may look like: 1wbw/2b/1ww/5/5 03478bcd.
 	 *
+	 * An extended FEN may also tell whether the current player moved
+	 * first (`f`) or second (`s`), and how many moves were played
+	 * since the start of the game: 1wbw/2b/1ww/5/5 03478bcd f 6.
+	 * Both fields are optional, by default every piece on the board
+	 * counts as a move. The side must match the parity of the ply
+	 * counter, which may not exceed the number of pieces.
+	 *
 	 * A Tile number is made by looking at the black pieces in a tile
 	 * representation. These black pieces will represent bits that are
 	 * on in our number, in the order indicated below.
@@ -78,84 +143,82 @@ impl Board {
 	 * 3 4
 	 * ```
 	 */
-	pub fn from_fen(fen: &str) -> Result<Board, &'static str> {
+	pub fn from_fen(fen: &str) -> Result<Board, ParseError> {
 		let mut black_mask = 0u64;
 		let mut white_mask = 0u64;
 
 		let mut x = 0; // Column
 		let mut y = 0; // Row
 
-		let mut chars = fen.chars().peekable();
+		let board_error = |offset, kind| ParseError::Board { offset, kind };
+
+		let mut separator = None;
 
 		// Board part.
-		while let Some(chr) = chars.next() {
-			match chr {
-				' ' if y < 4 => return Err("Not enough rows"),
-				' ' => break,
-				'/' if y == 4 => return Err("Too much rows"),
-				'/' if x > 5 => return Err("Too much cols"),
+		for (offset, chr) in fen.char_indices() {
+			let width = match chr {
+				' ' => {
+					separator = Some(offset);
+					break;
+				}
+				'/' if y == 4 => return Err(board_error(offset, RowCount(6))),
 				'/' => {
 					y += 1;
 					x = 0;
+					continue;
 				}
-				'1' => x += 1,
-				'2' => x += 2,
-				'3' => x += 3,
-				'4' => x += 4,
-				'5' => x += 5,
-				'b' => {
-					black_mask |= Move::mask_at(x, y);
-					x += 1;
-				}
-				'w' => {
-					white_mask |= Move::mask_at(x, y);
-					x += 1;
-				}
-				_ => return Err("invalid character"),
+				'1'..='5' => chr as u8 - b'0',
+				'b' | 'w' => 1,
+				_ => return Err(board_error(offset, InvalidCharacter(chr))),
+			};
+			if x + width > 5 {
+				return Err(board_error(offset, ColumnCount((x + width) as usize)));
+			}
+			match chr {
+				'b' => black_mask |= Move::mask_at(x, y),
+				'w' => white_mask |= Move::mask_at(x, y),
+				_ => (),
 			}
+			x += width;
 		}
 
-		if chars.peek().is_none() {
-			return Err("Incomplete FEN");
+		if y < 4 {
+			let offset = separator.unwrap_or(fen.len());
+			return Err(board_error(offset, RowCount(y as usize + 1)));
 		}
 
+		let Some(separator) = separator else {
+			return Err(ParseError::Tiles {
+				offset: fen.len(),
+				kind: UnexpectedEnd,
+			});
+		};
+
 		// Tiles part.
 
-		let mut tiles = TileSet::empty();
-		let mut i = 0;
-		while let Some(chr) = chars.next() {
-			match chr.to_digit(16) {
-				// No need for a guard there, digit may not be greater than 15.
-				Some(digit) => tiles = tiles.try_add(digit as u8).ok_or("Duplicate tile")?,
-				None => return Err("invalid character"),
-			}
-			if i > 7 {
-				return Err("Too much tiles");
-			}
-			i += 1;
-		}
+		let tiles_end = fen[separator + 1..]
+			.find(' ')
+			.map_or(fen.len(), |index| separator + 1 + index);
+		let tiles = TileSet::from_fen_part(&fen[separator + 1..tiles_end], separator + 1)?;
 
-		if i < 7 {
-			return Err("Not enough tiles");
-		}
+		// Extended part: side and ply counter.
 
-		let (current_player, opponent) = tiles.into();
 		let pieces_mask = black_mask | white_mask;
+		let pieces = pieces_mask.count_ones() as u8;
+		let (side, ply) = if tiles_end < fen.len() {
+			parse_turn(&fen[tiles_end + 1..], tiles_end + 1, pieces)?
+		} else {
+			(Side::from_ply(pieces), pieces)
+		};
 
-		Ok(Board {
-			pieces_mask,
-			black_mask,
-			current_player,
-			opponent,
-			played_tiles: TileSet::from_iter(filled_tiles(pieces_mask, black_mask)),
-		})
+		Ok(Board::from_parts(pieces_mask, black_mask, tiles, side, ply))
 	}
 
 	pub fn fen(&self) -> String {
 		let mut str = String::new();
 		for y in 0..5 {
 			if y > 0 {
-				str.push_str("/")
+				str.push('/')
 			}
 			let mut idx = 0;
 			for x in 0..5 {
@@ -176,30 +239,99 @@ impl Board {
 			}
 		}
 
-		format!("{} {}", str, self.current_player.fen_part())
+		let fen = format!("{} {}", str, self.current_player.fen_part());
+		if self.has_default_turn() {
+			fen
+		} else {
+			format!("{} {}", fen, self.turn_part())
+		}
+	}
+
+	/// The FEN, always including the side and ply counter.
+	pub fn extended_fen(&self) -> String {
+		let fen = self.fen();
+		if self.has_default_turn() {
+			format!("{} {}", fen, self.turn_part())
+		} else {
+			fen
+		}
+	}
+
+	/// Whether the side and ply counter match the pieces on the board,
+	/// in which case the short FEN holds every information.
+	fn has_default_turn(&self) -> bool {
+		let ply = self.pieces_mask.count_ones() as u8;
+		self.ply == ply && self.side == Side::from_ply(ply)
+	}
+
+	fn turn_part(&self) -> String {
+		let side = match self.side {
+			Side::First => 'f',
+			Side::Second => 's',
+		};
+		format!("{} {}", side, self.ply)
+	}
+
+	pub const fn opponent(&self) -> &Player {
+		&self.opponent
+	}
+
+	/// Whether the current player is the one who moved first.
+	pub const fn side(&self) -> Side {
+		self.side
+	}
+
+	/// Number of moves played since the start of the game.
+	pub const fn ply(&self) -> u8 {
+		self.ply
+	}
+
+	/// Encode the board in 64 bits, see `BoardWriter` to store
+	/// many boards. The board must be valid.
+	pub fn encode(&self) -> u64 {
+		codec::encode(self)
+	}
+
+	/// Decode a board encoded with `Board::encode`, checking that it
+	/// is valid.
+	pub fn decode(code: u64) -> Result<Board, DecodeError> {
+		codec::decode(code)
+	}
+
+	/// Color of the piece at (x, y), if any.
+	pub const fn color_at(&self, x: u8, y: u8) -> Option<Color> {
+		let mask = Move::mask_at(x, y);
+		if self.pieces_mask & mask == 0 {
+			None
+		} else if self.black_mask & mask == 0 {
+			Some(Color::White)
+		} else {
+			Some(Color::Black)
+		}
 	}
 
+	/// Every legal move, by square then black before white.
 	pub gen fn possible_moves(&self) -> Move {
-		let board_full_mask = 0b0000000_0111110_0111110_0111110_0111110_0111110_0000000u64;
-		let mut available_spots = !self.pieces_mask & board_full_mask;
-		while available_spots != 0 {
-			// TODO(perf): benchmark the most efficient way to iterate over bits
-			// and extract that in a util crate.
-			//
-			// See https://graphics.stanford.edu/~seander/bithacks.html
-			let i = available_spots.trailing_zeros();
-			let mov_mask = 1u64 << i;
-			available_spots ^= mov_mask;
-
-			for color in [Color::Black, Color::White] {
-				let mov = Move::from_mask(mov_mask, color);
-				if self.is_move_possible(&mov) {
-					yield mov;
-				}
+		let (black_legal, white_legal) = self.legal_moves();
+		let mut squares = black_legal | white_legal;
+		while squares != 0 {
+			let mov_mask = squares & squares.wrapping_neg();
+			squares ^= mov_mask;
+
+			if black_legal & mov_mask != 0 {
+				yield Move::from_mask(mov_mask, Color::Black);
+			}
+			if white_legal & mov_mask != 0 {
+				yield Move::from_mask(mov_mask, Color::White);
 			}
 		}
 	}
 
+	/// Squares where a black and a white move are legal, as 7x7 masks.
+	pub fn legal_moves(&self) -> (u64, u64) {
+		movegen::legal_moves(self.pieces_mask, self.black_mask, self.played_tiles)
+	}
+
 	pub fn is_move_possible(&self, mov: &Move) -> bool {
 		if mov.mask & self.pieces_mask != 0 {
 			return false;
@@ -225,55 +357,326 @@ impl Board {
 		})
 	}
 
+	/// Check every invariant of a position. FENs may describe
+	/// impossible positions and public fields may be changed by hand,
+	/// but legal moves keep a board valid: debug builds check it on
+	/// each `play` and `undo` of a valid board, and games only start
+	/// from valid boards.
+	pub fn validate(&self) -> Result<(), Vec<Violation>> {
+		let mut violations = vec![];
+
+		let outside = (self.pieces_mask | self.black_mask) & !BOARD_MASK;
+		if outside != 0 {
+			violations.push(Violation::OutOfBounds(outside));
+		}
+		let black_on_empty = self.black_mask & !self.pieces_mask;
+		if black_on_empty != 0 {
+			violations.push(Violation::BlackOnEmptySquare(black_on_empty));
+		}
+
+		let mut filled = TileSet::empty();
+		for tile in self.filled_tiles() {
+			match filled.try_add(tile) {
+				Some(tiles) => filled = tiles,
+				None => violations.push(Violation::DuplicateTile(tile)),
+			}
+		}
+		if filled != self.played_tiles {
+			violations.push(Violation::PlayedTilesMismatch);
+		}
+
+		let tiles = self.current_player.tiles();
+		if tiles.count() != 8 || self.opponent.tiles() != !tiles {
+			violations.push(Violation::TileDistribution);
+		}
+
+		if self.hash != zobrist::hash(self.pieces_mask, self.black_mask, tiles) {
+			violations.push(Violation::StaleHash);
+		}
+
+		if self.side != Side::from_ply(self.ply) {
+			violations.push(Violation::SideMismatch);
+		}
+
+		if self.ply as u32 > self.pieces_mask.count_ones() {
+			violations.push(Violation::PlyOutOfRange(self.ply));
+		}
+
+		if violations.is_empty() {
+			Ok(())
+		} else {
+			Err(violations)
+		}
+	}
+
+	/// A Zobrist hash of the position: square colors and the tiles
+	/// of the current player. Two boards with the same hash are most
+	/// likely the same position.
+	pub const fn hash(&self) -> u64 {
+		self.hash
+	}
+
 	pub fn is_terminal(&self) -> bool {
-		self.played_tiles.is_full() || self.possible_moves().next().is_none()
+		self.played_tiles.is_full() || self.legal_moves() == (0, 0)
 	}
 
 	pub fn is_winning(&self) -> bool {
 		self.is_terminal() && self.current_score() > 0
 	}
 
+	/**
+	 * Tiles that may still be completed: tiles not on the board that
+	 * fit the pieces of at least one incomplete 2x2 window. Filling a
+	 * window may block another one, so a reachable tile is not always
+	 * completed by the end of the game.
+	 */
+	pub fn reachable_tiles(&self) -> TileSet {
+		self.windows().fold(TileSet::empty(), |reachable, window| {
+			reachable | window.achievable
+		})
+	}
+
+	/**
+	 * Lowest and highest final scores of the current player, the
+	 * opponent's bounds being the opposite. The current score may at
+	 * best gain every reachable tile of the current player, and at
+	 * worst lose every reachable tile of the opponent, one per
+	 * incomplete window at most.
+	 *
+	 * The bounds are intentionally loose. Each one is capped by the
+	 * incomplete windows on its own, as the best and worst endings
+	 * are different games, so the range may span twice as many tiles
+	 * as there are windows left. Windows blocked by later moves are
+	 * not taken into account either, and `is_decided` may miss
+	 * decided positions.
+	 */
+	pub fn score_bounds(&self) -> RangeInclusive<i8> {
+		let score = self.current_score();
+		if self.is_terminal() {
+			return score..=score;
+		}
+		let windows = self
+			.windows()
+			.filter(|window| !window.is_complete())
+			.count();
+		let reachable = self.reachable_tiles();
+		let gain = (reachable & self.current_player.tiles()).len().min(windows);
+		let loss = (reachable & self.opponent.tiles()).len().min(windows);
+		score - loss as i8..=score + gain as i8
+	}
+
+	/// Whether the result of the game is known, whatever the moves left.
+	/// Only as precise as `score_bounds`, some decided positions are
+	/// not detected.
+	pub fn is_decided(&self) -> bool {
+		let bounds = self.score_bounds();
+		*bounds.start() > 0 || *bounds.end() < 0 || bounds.start() == bounds.end()
+	}
+
+	/// The 16 windows of the board, row by row.
+	pub gen fn windows(&self) -> Window {
+		for y in 0..4 {
+			for x in 0..4 {
+				let mut window = Window {
+					x,
+					y,
+					filled: 0,
+					tile: None,
+					achievable: TileSet::empty(),
+				};
+				let mut filled = 0;
+				let mut black = 0;
+				for (bit, (x, y)) in window.squares().into_iter().enumerate() {
+					match self.color_at(x, y) {
+						Some(Color::Black) => {
+							filled |= 1 << bit;
+							black |= 1 << bit;
+						}
+						Some(Color::White) => filled |= 1 << bit,
+						None => continue,
+					}
+					window.filled += 1;
+				}
+				if window.is_complete() {
+					window.tile = Some(black);
+				} else {
+					window.achievable =
+						TileSet::union_of((0..16).filter(|tile| tile & filled == black))
+							- self.played_tiles;
+				}
+				yield window;
+			}
+		}
+	}
+
 	fn filled_tiles(&self) -> FilledTilesIterator {
 		filled_tiles(self.pieces_mask, self.black_mask)
 	}
 
+	/// The same position seen through `symmetry`: squares are moved,
+	/// colors may be swapped, and tiles are renumbered accordingly.
+	pub fn transform(&self, symmetry: Symmetry) -> Board {
+		let mut pieces_mask = 0;
+		let mut black_mask = 0;
+		for y in 0..5 {
+			for x in 0..5 {
+				let position = Move::mask_at(x, y);
+				if self.pieces_mask & position == 0 {
+					continue;
+				}
+				let (new_x, new_y) = symmetry.apply_square(x, y);
+				let new_position = Move::mask_at(new_x, new_y);
+				pieces_mask |= new_position;
+				if (self.black_mask & position != 0) != symmetry.swaps_colors() {
+					black_mask |= new_position;
+				}
+			}
+		}
+		let tiles = TileSet::union_of(
+			self.current_player
+				.tiles()
+				.map(|tile| symmetry.apply_tile(tile)),
+		);
+		Board {
+			side: self.side,
+			ply: self.ply,
+			..Board::with_players(pieces_mask, black_mask, Player::pair_unchecked(tiles))
+		}
+	}
+
+	/// The representative of this position among its 16 symmetries,
+	/// and the symmetry that leads to it. Moves found on the canonical
+	/// board map back with `Symmetry::revert_move`.
+	pub fn canonical(&self) -> (Board, Symmetry) {
+		Symmetry::all()
+			.map(|symmetry| (self.transform(symmetry), symmetry))
+			.min_by_key(|(board, _)| {
+				(
+					board.pieces_mask,
+					board.black_mask,
+					board.current_player.tiles().bits(),
+				)
+			})
+			.expect("there is at least the identity")
+	}
+
+	/**
+	 * Apply a move and report what it did: tiles completed, whose
+	 * they are and how the score of the player who moved changes.
+	 * Unlike `Board::next`, an illegal move comes with the reason
+	 * it was rejected.
+	 */
+	pub fn apply(&self, mov: &Move) -> Result<MoveOutcome, IllegalMove> {
+		let mut board = *self;
+		let undo = board.play(*mov)?;
+		let completed = board.played_tiles - undo.played_tiles;
+		let tiles: Vec<(u8, Side)> = completed
+			.map(|tile| {
+				if self.current_player.has_tile(tile) {
+					(tile, self.side)
+				} else {
+					(tile, !self.side)
+				}
+			})
+			.collect();
+		let score_delta = tiles
+			.iter()
+			.map(|&(_, side)| if side == self.side { 1 } else { -1 })
+			.sum();
+		Ok(MoveOutcome {
+			mov: *mov,
+			board,
+			tiles,
+			score_delta,
+		})
+	}
+
 	/// Apply a move and check for validity.
 	pub fn next(&self, mov: &Move) -> Option<Board> {
+		let mut board = *self;
+		board.play(*mov).ok().map(|_| board)
+	}
+
+	/// Apply a move in place and check for validity. The returned
+	/// `Undo` takes the board back to its current state, without
+	/// copying the whole board at each move.
+	pub fn play(&mut self, mov: Move) -> Result<Undo, IllegalMove> {
+		#[cfg(debug_assertions)]
+		let valid = self.validate().is_ok();
 		let pos = mov.mask;
-		if pos & self.pieces_mask != 0 {
-			return None;
+		let played_tiles = self.played_tiles | self.completed_by(&mov)?;
+		let undo = Undo {
+			mask: pos,
+			played_tiles: self.played_tiles,
+		};
+		self.pieces_mask |= pos;
+		if mov.color == Color::Black {
+			self.black_mask |= pos;
 		}
-		let tiles_from_move = self.tiles_from(mov)?;
-		let played_tiles = self.played_tiles.try_union(&tiles_from_move)?;
-		Some(Board {
-			pieces_mask: self.pieces_mask | pos,
-			black_mask: if mov.color == Color::Black {
-				self.black_mask | pos
-			} else {
-				self.black_mask
-			},
-			current_player: self.opponent,
-			opponent: self.current_player,
-			played_tiles,
-		})
+		self.played_tiles = played_tiles;
+		self.hash ^= zobrist::square_key(pos, mov.color) ^ zobrist::SWAP_PLAYERS_KEY;
+		std::mem::swap(&mut self.current_player, &mut self.opponent);
+		self.side = !self.side;
+		self.ply += 1;
+		#[cfg(debug_assertions)]
+		debug_assert!(!valid || self.validate().is_ok(), "{} broke the board", mov);
+		Ok(undo)
+	}
+
+	/// Take back the last move played with `Board::play`. Undos
+	/// must be applied in the reverse order of their moves.
+	pub fn undo(&mut self, undo: Undo) {
+		debug_assert!(self.pieces_mask & undo.mask != 0, "square should be filled");
+		#[cfg(debug_assertions)]
+		let valid = self.validate().is_ok();
+		let color = if self.black_mask & undo.mask == 0 {
+			Color::White
+		} else {
+			Color::Black
+		};
+		self.hash ^= zobrist::square_key(undo.mask, color) ^ zobrist::SWAP_PLAYERS_KEY;
+		self.pieces_mask &= !undo.mask;
+		self.black_mask &= !undo.mask;
+		self.played_tiles = undo.played_tiles;
+		std::mem::swap(&mut self.current_player, &mut self.opponent);
+		self.side = !self.side;
+		self.ply -= 1;
+		#[cfg(debug_assertions)]
+		debug_assert!(!valid || self.validate().is_ok(), "undo broke the board");
 	}
 
 	/// A dup move is a move that generates two times the same tile, hence it is invalid.
 	fn already_played_or_dup_move<'a>(&'a self, mov: &'a Move) -> bool {
-		let Some(tiles_from_move) = self.tiles_from(&mov) else {
+		let Ok(tiles_from_move) = self.tiles_from(mov) else {
 			return true; // dup move
 		};
-		match self.played_tiles.try_union(&tiles_from_move) {
-			Some(_) => false,
-			None => true, // already played
+		self.played_tiles.try_union(&tiles_from_move).is_none()
+	}
+
+	/// Tiles completed by a move, or the reason it may not be played.
+	fn completed_by(&self, mov: &Move) -> Result<TileSet, IllegalMove> {
+		let illegal = |kind| IllegalMove { mov: *mov, kind };
+		if mov.mask & BOARD_MASK == 0 {
+			return Err(illegal(IllegalMoveKind::OutOfBounds));
+		}
+		if mov.mask & self.pieces_mask != 0 {
+			return Err(illegal(IllegalMoveKind::Occupied));
+		}
+		let tiles = self
+			.tiles_from(mov)
+			.map_err(|tile| illegal(IllegalMoveKind::DuplicateTile(tile)))?;
+		if let Some(tile) = (tiles & self.played_tiles).next() {
+			return Err(illegal(IllegalMoveKind::AlreadyPlayed(tile)));
 		}
+		Ok(tiles)
 	}
 
 	/**
 	 * From a empty square, checks which tiles could be created if we play
 	 * a given move. This function doesn't check for emptiness of the square.
+	 * A tile created twice is returned as an error.
 	 */
-	fn tiles_from<'a>(&self, mov: &'a Move) -> Option<TileSet> {
+	fn tiles_from(&self, mov: &Move) -> Result<TileSet, u8> {
 		let mut tiles = TileSet::empty();
 		// A new move impacts up to a 3x3 area.
 		// We can represent it with a number
@@ -297,59 +700,63 @@ impl Board {
 		};
 
 		for tile in filled_tiles(new_mask, new_black_mask) {
-			tiles = tiles.try_add(tile)?;
+			tiles = tiles.try_add(tile).ok_or(tile)?;
 		}
 
-		Some(tiles)
+		Ok(tiles)
 	}
 
+	/// Board and tiles of both players, for a terminal. Use a
+	/// `Renderer` for other outputs.
 	pub fn for_console(&self) -> String {
-		let mut str = String::new();
-		str.push_str(&self.fen());
-		str.push('\n');
-
-		let filled_tiles: TileSet = self.filled_tiles().collect();
-		str.push_str(&self.opponent.for_console(&filled_tiles));
-
-		let spacing = " ".repeat((46/* tiles len */ - 12/* board len */) / 2);
-
-		str.push('\n');
-		str.push_str(&spacing);
-		str.push_str("   a b c d e\n");
-		for y in 0..5 {
-			str.push_str(&spacing);
-			str.push_str(&(y + 1).to_string());
-			str.push_str(" \x1b[47m");
-
-			for x in 0..5 {
-				let position = Move::mask_at(x, y);
-
-				str.push_str(" ");
+		Ansi.board(self)
+	}
+}
 
-				if self.pieces_mask & position == 0 {
-					str.push_str("\x1b[30m");
-					match (
-						self.next(&Move::black(x, y)).is_none(),
-						self.next(&Move::white(x, y)).is_none(),
-					) {
-						(false, false) => str.push('·'),
-						(false, true) => str.push('b'),
-						(true, false) => str.push('w'),
-						(true, true) => str.push('x'),
-					}
-				} else if self.black_mask & position != 0 {
-					str.push_str(format!("{}●", Color::Black).as_str());
-				} else {
-					str.push_str(format!("{}●", Color::White).as_str());
-				}
-			}
-			str.push_str(" \x1b[0m\n");
+/// Parse the extended part of a FEN, i.e. a side optionally
+/// followed by a ply counter, `pieces` being both the default and
+/// the largest ply. `offset` is the position of `input` in the
+/// whole FEN.
+fn parse_turn(input: &str, offset: usize, pieces: u8) -> Result<(Side, u8), ParseError> {
+	let error = |index, kind| ParseError::Turn {
+		offset: offset + index,
+		kind,
+	};
+	let mut chars = input.char_indices();
+	let side = match chars.next() {
+		Some((_, 'f')) => Side::First,
+		Some((_, 's')) => Side::Second,
+		Some((index, chr)) => return Err(error(index, InvalidCharacter(chr))),
+		None => return Err(error(0, UnexpectedEnd)),
+	};
+	let side_mismatch = |ply| {
+		if side == Side::from_ply(ply) {
+			Ok((side, ply))
+		} else {
+			Err(error(0, SideMismatch))
 		}
-		str.push('\n');
-		str.push('\n');
-		str.push_str(&self.current_player.for_console(&filled_tiles));
-
-		str
+	};
+	match chars.next() {
+		None => return side_mismatch(pieces),
+		Some((_, ' ')) => (),
+		Some((index, chr)) => return Err(error(index, InvalidCharacter(chr))),
+	}
+	let mut ply: Option<u8> = None;
+	for (index, chr) in chars {
+		let Some(digit) = chr.to_digit(10) else {
+			return Err(error(index, InvalidCharacter(chr)));
+		};
+		ply = Some(
+			ply.unwrap_or(0)
+				.checked_mul(10)
+				.and_then(|ply| ply.checked_add(digit as u8))
+				.ok_or(error(index, TooLarge))?,
+		);
+	}
+	match ply {
+		Some(ply) if ply > pieces => Err(error(2, TooLarge)),
+		Some(ply) => side_mismatch(ply),
+		None => Err(error(input.len(), UnexpectedEnd)),
 	}
 }
 
@@ -389,7 +796,7 @@ impl Iterator for FilledTilesIterator {
 	}
 }
 
-fn tile_at(black_mask: &u64, top_left: u64) -> u8 {
+pub(crate) fn tile_at(black_mask: &u64, top_left: u64) -> u8 {
 	let top_left_shift = top_left.trailing_zeros();
 
 	let a = (black_mask & top_left) >> top_left_shift;
@@ -401,7 +808,7 @@ fn tile_at(black_mask: &u64, top_left: u64) -> u8 {
 }
 
 impl std::convert::TryFrom<&str> for Board {
-	type Error = &'static str;
+	type Error = ParseError;
 	fn try_from(s: &str) -> Result<Self, Self::Error> {
 		Board::from_fen(s)
 	}
@@ -460,10 +867,238 @@ mod tests {
 		assert!(board.next(&mov).is_none());
 	}
 
+	#[test]
+	fn test_play_and_undo() {
+		let original = Board::from_fen("bb1ww/www1w/1bb/1bww/2w 013457df").unwrap();
+		let mut board = original;
+		let mut undos = vec![];
+		loop {
+			let Some(mov) = board.possible_moves().next() else {
+				break;
+			};
+			let expected = board.next(&mov).unwrap();
+			undos.push(board.play(mov).unwrap());
+			assert_eq!(board, expected);
+		}
+		let illegal = Move::try_from("wa1").unwrap();
+		assert_eq!(
+			board.play(illegal),
+			Err(IllegalMove {
+				mov: illegal,
+				kind: IllegalMoveKind::Occupied
+			})
+		);
+		while let Some(undo) = undos.pop() {
+			board.undo(undo);
+		}
+		assert_eq!(board, original);
+	}
+
+	#[test]
+	fn test_apply() {
+		// Second player to move, black at b2 completes tile 8, owned
+		// by the first player.
+		let board = Board::from_fen("ww/w/// 01234567").unwrap();
+		let outcome = board.apply(&Move::black(1, 1)).unwrap();
+		assert_eq!(outcome.tiles, vec![(8, Side::First)]);
+		assert_eq!(outcome.score_delta, -1);
+		assert_eq!(outcome.board, board.next(&Move::black(1, 1)).unwrap());
+
+		let outcome = board.apply(&Move::white(1, 1)).unwrap();
+		assert_eq!(outcome.tiles, vec![(0, Side::Second)]);
+		assert_eq!(outcome.score_delta, 1);
+
+		let illegal = |mov, kind| Err(IllegalMove { mov, kind });
+		let board = Board::from_fen("www/w1w/www// 01234567").unwrap();
+		assert_eq!(
+			board.apply(&Move::white(1, 1)),
+			illegal(Move::white(1, 1), IllegalMoveKind::DuplicateTile(0))
+		);
+
+		let board = Board::from_fen("ww1ww/ww2w/// 89abcdef").unwrap();
+		assert_eq!(
+			board.apply(&Move::white(3, 1)),
+			illegal(Move::white(3, 1), IllegalMoveKind::AlreadyPlayed(0))
+		);
+		assert_eq!(
+			board.apply(&Move::white(0, 0)),
+			illegal(Move::white(0, 0), IllegalMoveKind::Occupied)
+		);
+		assert_eq!(
+			board.apply(&Move::new(5, 0, Color::Black)),
+			illegal(Move::new(5, 0, Color::Black), IllegalMoveKind::OutOfBounds)
+		);
+	}
+
+	#[test]
+	fn test_reachable_tiles() {
+		let board = Board::empty();
+		assert!(board.reachable_tiles().is_full());
+		assert_eq!(board.score_bounds(), -8..=8);
+		assert!(!board.is_decided());
+
+		// Only windows around b3 and d5 are incomplete. They fit tiles
+		// 0, 2, 5, 6, 8, 10, 12 and 13, of which 2, 5, 8 and 10 are
+		// already on the board.
+		let board = Board::from_fen("bwwbw/wwbbw/w1wwb/wbbwb/wwb1w 12346cdf").unwrap();
+		assert_eq!(
+			board.reachable_tiles(),
+			TileSet::try_from([0, 6, 12, 13]).unwrap()
+		);
+		// Current score is -2, tiles 6, c and d may be won, 0 may be lost.
+		assert_eq!(board.score_bounds(), -3..=1);
+		assert!(!board.is_decided());
+
+		let board = board.next(&Move::black(3, 4)).unwrap();
+		assert!(board.is_decided());
+	}
+
+	#[test]
+	fn test_windows() {
+		let board = Board::from_fen("bwwbw/wwbbw/w1wwb/wbbwb/wwb1w 12346cdf").unwrap();
+		let windows: Vec<Window> = board.windows().collect();
+		assert_eq!(windows.len(), 16);
+		assert_eq!(
+			TileSet::try_from_iter(windows.iter().filter_map(|window| window.tile)),
+			Ok(board.played_tiles)
+		);
+		let window = windows[0];
+		assert_eq!((window.x, window.y, window.filled), (0, 0, 4));
+		// Only the top left square is black.
+		assert_eq!(window.tile, Some(0b0001));
+		assert!(window.achievable.is_empty());
+		// Window at a2 lacks b3, tile 8 is already on the board.
+		let window = windows[4];
+		assert_eq!((window.x, window.y, window.filled), (0, 1, 3));
+		assert_eq!(window.tile, None);
+		assert_eq!(window.achievable, TileSet::try_from([0]).unwrap());
+	}
+
+	#[test]
+	fn test_legal_moves() {
+		use rand::SeedableRng;
+		use rand::rngs::StdRng;
+		use rand::seq::IteratorRandom;
+
+		// Compare with a square by square check on random games.
+		let mut rng = StdRng::seed_from_u64(7);
+		for _ in 0..50 {
+			let mut board = Board::random_empty(&mut rng);
+			loop {
+				let (black_legal, white_legal) = board.legal_moves();
+				for x in 0..5 {
+					for y in 0..5 {
+						let position = Move::mask_at(x, y);
+						assert_eq!(
+							black_legal & position != 0,
+							board.is_move_possible(&Move::black(x, y)),
+							"black at {} on {}",
+							Move::black(x, y),
+							board
+						);
+						assert_eq!(
+							white_legal & position != 0,
+							board.is_move_possible(&Move::white(x, y)),
+							"white at {} on {}",
+							Move::white(x, y),
+							board
+						);
+					}
+				}
+				let Some(mov) = board.possible_moves().choose(&mut rng) else {
+					break;
+				};
+				board.play(mov).unwrap();
+			}
+		}
+	}
+
+	#[test]
+	fn test_hash() {
+		let play_all = |moves: [Move; 4]| {
+			let mut board = Board::empty();
+			let undos = moves.map(|mov| board.play(mov).unwrap());
+			(board, undos)
+		};
+		let (board, mut undos) = play_all([
+			Move::black(0, 0),
+			Move::white(4, 4),
+			Move::black(2, 2),
+			Move::white(1, 1),
+		]);
+		let (transposed, _) = play_all([
+			Move::black(2, 2),
+			Move::white(4, 4),
+			Move::black(0, 0),
+			Move::white(1, 1),
+		]);
+		assert_eq!(board.hash(), transposed.hash());
+		assert_eq!(board.hash(), Board::from_fen(&board.fen()).unwrap().hash());
+
+		let mut board = board;
+		undos.reverse();
+		for undo in undos {
+			board.undo(undo);
+		}
+		assert_eq!(board.hash(), Board::empty().hash());
+
+		// The same pieces with the other player to move.
+		assert_ne!(
+			Board::from_fen("1wbw/2b/1ww// 01234567").unwrap().hash(),
+			Board::from_fen("1wbw/2b/1ww// 89abcdef").unwrap().hash()
+		);
+	}
+
+	#[test]
+	fn test_canonical() {
+		let board = Board::from_fen("bb1ww/www1w/1bb/1bww/2w 013457df").unwrap();
+		let (canonical, symmetry) = board.canonical();
+		assert_eq!(board.transform(symmetry), canonical);
+		for other in Symmetry::all() {
+			let transformed = board.transform(other);
+			assert_eq!(transformed.current_score(), board.current_score());
+			assert_eq!(transformed.played_tiles.count(), board.played_tiles.count());
+			assert_eq!(transformed.canonical().0, canonical);
+			for mov in board.possible_moves() {
+				assert_eq!(
+					board.next(&mov).unwrap().transform(other),
+					transformed.next(&other.apply_move(mov)).unwrap()
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_validate() {
+		assert_eq!(Board::empty().validate(), Ok(()));
+		let board = Board::from_fen("bb1ww/www1w/1bb/1bww/2w 013457df").unwrap();
+		assert_eq!(board.validate(), Ok(()));
+
+		// Tile 0 (all white) is completed twice.
+		let board = Board::from_fen("ww1ww/ww1ww/5/5/5 01234567").unwrap();
+		assert_eq!(board.validate(), Err(vec![Violation::DuplicateTile(0)]));
+
+		let mut board = Board::from_fen("bb1ww/www1w/1bb/1bww/2w 013457df").unwrap();
+		board.black_mask |= 1;
+		board.played_tiles = TileSet::empty();
+		assert_eq!(
+			board.validate(),
+			Err(vec![
+				Violation::OutOfBounds(1),
+				Violation::BlackOnEmptySquare(1),
+				Violation::PlayedTilesMismatch,
+			])
+		);
+
+		let mut board = Board::empty();
+		board.pieces_mask = Move::mask_at(0, 0);
+		assert_eq!(board.validate(), Err(vec![Violation::StaleHash]));
+	}
+
 	#[test]
 	fn test_from_fen_set_played_tiles() {
 		let board = Board::from_fen("bb1ww/www1w/1bbw/1bww/2w 2689abce").unwrap();
-		let expected_played_tiles: TileSet = TileSet::new(0b0001_0000_1000_1010);
+		let expected_played_tiles: TileSet = TileSet::from_bits(0b0001_0000_1000_1010);
 		assert_eq!(board.played_tiles, expected_played_tiles);
 	}
 
@@ -496,7 +1131,10 @@ mod tests {
 		let mov = Move::try_from("wd2").unwrap();
 		let board = Board::from_fen("2wwb/2w1b/2wbb/5/5 01234567").unwrap();
 		println!("{}\nChecking tiles from move {}", board, mov);
-		assert_eq!(board.tiles_from(&mov).unwrap(), vec![0, 10, 8, 14].into())
+		assert_eq!(
+			board.tiles_from(&mov).unwrap(),
+			TileSet::try_from([0, 10, 8, 14]).unwrap()
+		)
 	}
 
 	#[test]
@@ -514,9 +1152,161 @@ mod tests {
 		assert_eq!(Board::from_fen(fen).unwrap().fen(), "//// 01234567");
 	}
 
+	#[test]
+	fn extended_fen() {
+		let board = Board::from_fen("1wbw/2b/1ww// 03478bcd").unwrap();
+		assert_eq!(board.side(), Side::First);
+		assert_eq!(board.ply(), 6);
+		assert_eq!(board.extended_fen(), "1wbw/2b/1ww// 03478bcd f 6");
+
+		// A puzzle starting with pieces already on the board.
+		let fen = "1wbw/2b/1ww// 03478bcd s 1";
+		let board = Board::from_fen(fen).unwrap();
+		assert_eq!(board.validate(), Ok(()));
+		assert_eq!((board.side(), board.ply()), (Side::Second, 1));
+		assert_eq!(board.fen(), fen);
+		let next = board.next(&Move::black(0, 4)).unwrap();
+		assert_eq!(next.fen(), "1wbw/2b/1ww//b 12569aef f 2");
+
+		let board = Board::from_fen("1wbw/2b/1ww// 03478bcd f 4").unwrap();
+		assert_eq!((board.side(), board.ply()), (Side::First, 4));
+
+		// Legal moves keep the ply in range.
+		let mut board = Board::from_fen("bb1ww/www1w/1bb/1bww/2w 013457df s 13").unwrap();
+		let mov = board.possible_moves().next().unwrap();
+		board.play(mov).unwrap();
+		assert_eq!(board.ply(), 14);
+		assert_eq!(board.validate(), Ok(()));
+
+		let mut board = Board::empty();
+		board.ply = 1;
+		board.side = Side::Second;
+		assert_eq!(board.validate(), Err(vec![Violation::PlyOutOfRange(1)]));
+	}
+
+	#[test]
+	fn from_fen_errors() {
+		for (fen, error) in [
+			(
+				"5/5/5/5/5",
+				ParseError::Tiles {
+					offset: 9,
+					kind: UnexpectedEnd,
+				},
+			),
+			(
+				"5/5/5/5 01234567",
+				ParseError::Board {
+					offset: 7,
+					kind: RowCount(4),
+				},
+			),
+			(
+				"5/5/5/5/5/ 01234567",
+				ParseError::Board {
+					offset: 9,
+					kind: RowCount(6),
+				},
+			),
+			(
+				"5/5/5/5/6 01234567",
+				ParseError::Board {
+					offset: 8,
+					kind: InvalidCharacter('6'),
+				},
+			),
+			(
+				"5/bw4/5/5/5 01234567",
+				ParseError::Board {
+					offset: 4,
+					kind: ColumnCount(6),
+				},
+			),
+			(
+				"5/5/4bw/5/5 01234567",
+				ParseError::Board {
+					offset: 6,
+					kind: ColumnCount(6),
+				},
+			),
+			(
+				"5/5/5/5/5 0123456",
+				ParseError::Tiles {
+					offset: 17,
+					kind: TileCount(7),
+				},
+			),
+			(
+				"5/5/5/5/5 012345678",
+				ParseError::Tiles {
+					offset: 18,
+					kind: TileCount(9),
+				},
+			),
+			(
+				"5/5/5/5/5 01234566",
+				ParseError::Tiles {
+					offset: 17,
+					kind: DuplicateTile(6),
+				},
+			),
+			(
+				"5/5/5/5/5 0123456g",
+				ParseError::Tiles {
+					offset: 17,
+					kind: InvalidCharacter('g'),
+				},
+			),
+			(
+				"5/5/5/5/5 01234567 x",
+				ParseError::Turn {
+					offset: 19,
+					kind: InvalidCharacter('x'),
+				},
+			),
+			(
+				"5/5/5/5/5 01234567 f ",
+				ParseError::Turn {
+					offset: 21,
+					kind: UnexpectedEnd,
+				},
+			),
+			(
+				"5/5/5/5/5 01234567 f 256",
+				ParseError::Turn {
+					offset: 23,
+					kind: TooLarge,
+				},
+			),
+			(
+				"1wbw/2b/1ww// 03478bcd f 8",
+				ParseError::Turn {
+					offset: 25,
+					kind: TooLarge,
+				},
+			),
+			(
+				"1wbw/2b/1ww// 03478bcd s",
+				ParseError::Turn {
+					offset: 23,
+					kind: SideMismatch,
+				},
+			),
+			(
+				"1wbw/2b/1ww// 03478bcd s 4",
+				ParseError::Turn {
+					offset: 23,
+					kind: SideMismatch,
+				},
+			),
+		] {
+			assert_eq!(Board::from_fen(fen).err(), Some(error), "for FEN {:?}", fen);
+		}
+	}
+
 	#[bench]
 	fn bench_is_move_possible(b: &mut Bencher) {
-		for fen in vec![
+		for fen in [
 			"2b1b/wwb1w/w1bw/bw1w/bw2b 137abcdf",
 			"1bwwb/wwwwb/b/w2wb/2b1w 14579ace",
 			"bw/1ww/1bb1w//b1ww 0367abce",
@@ -0,0 +1,260 @@
+// Fixed-width binary encoding of a board, in 64 bits.
+//
+// Squares are read as a base 3 number (0 empty, 1 white, 2 black),
+// the square (x, y) being digit `5 * y + x`. 3^25 fits in 40 bits:
+//
+//   bits  0..40  squares
+//   bits 40..56  tiles of the current player, tile `n` being bit `n`
+//   bits 56..61  ply counter
+//   bit  61      set when the current player moved second
+//   bits 62..64  unused, always 0
+
+use std::io::{self, Read, Write};
+
+use crate::board::Board;
+use crate::color::Color;
+use crate::error::Violation;
+use crate::mov::Move;
+use crate::player::Side;
+use crate::tileset::TileSet;
+
+const SQUARES_BITS: u32 = 40;
+const SQUARES_LIMIT: u64 = 3u64.pow(25);
+const TILES_SHIFT: u32 = SQUARES_BITS;
+const PLY_SHIFT: u32 = TILES_SHIFT + 16;
+const PLY_MASK: u64 = 0b11111;
+const SIDE_BIT: u64 = 1 << (PLY_SHIFT + 5);
+const UNUSED_MASK: u64 = !0 << (PLY_SHIFT + 6);
+
+/// Error returned when decoding a board, see `Board::decode`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum DecodeError {
+	/// The bits are not the encoding of any board.
+	Malformed(u64),
+	/// The board cannot happen in a game.
+	Invalid(Vec<Violation>),
+}
+
+impl std::fmt::Display for DecodeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DecodeError::Malformed(code) => write!(f, "malformed board code {:#018x}", code),
+			DecodeError::Invalid(violations) => write!(
+				f,
+				"invalid board: {}",
+				violations
+					.iter()
+					.map(|violation| violation.to_string())
+					.collect::<Vec<String>>()
+					.join(", ")
+			),
+		}
+	}
+}
+
+impl std::error::Error for DecodeError {}
+
+pub(crate) fn encode(board: &Board) -> u64 {
+	let mut squares = 0;
+	for index in (0..25).rev() {
+		let digit = match board.color_at(index % 5, index / 5) {
+			None => 0,
+			Some(Color::White) => 1,
+			Some(Color::Black) => 2,
+		};
+		squares = squares * 3 + digit;
+	}
+	let side = match board.side() {
+		Side::First => 0,
+		Side::Second => SIDE_BIT,
+	};
+	squares
+		| (board.current_player.tiles().bits() as u64) << TILES_SHIFT
+		// Valid boards have at most 25 plies, masking keeps an invalid
+		// one from spilling into the side and unused bits.
+		| (board.ply() as u64 & PLY_MASK) << PLY_SHIFT
+		| side
+}
+
+pub(crate) fn decode(code: u64) -> Result<Board, DecodeError> {
+	let mut squares = code & ((1 << SQUARES_BITS) - 1);
+	if squares >= SQUARES_LIMIT || code & UNUSED_MASK != 0 {
+		return Err(DecodeError::Malformed(code));
+	}
+	let mut pieces_mask = 0;
+	let mut black_mask = 0;
+	for index in 0..25 {
+		let mask = Move::mask_at(index % 5, index / 5);
+		match squares % 3 {
+			0 => (),
+			1 => pieces_mask |= mask,
+			_ => {
+				pieces_mask |= mask;
+				black_mask |= mask;
+			}
+		}
+		squares /= 3;
+	}
+	let tiles = TileSet::from_bits((code >> TILES_SHIFT) as u16);
+	let ply = ((code >> PLY_SHIFT) & PLY_MASK) as u8;
+	let side = if code & SIDE_BIT == 0 {
+		Side::First
+	} else {
+		Side::Second
+	};
+	let board = Board::from_parts(pieces_mask, black_mask, tiles, side, ply);
+	board.validate().map_err(DecodeError::Invalid)?;
+	Ok(board)
+}
+
+/// Write boards to a stream, 8 little endian bytes each.
+pub struct BoardWriter<W: Write> {
+	inner: W,
+}
+
+impl<W: Write> BoardWriter<W> {
+	pub fn new(inner: W) -> Self {
+		BoardWriter { inner }
+	}
+
+	pub fn write(&mut self, board: &Board) -> io::Result<()> {
+		self.inner.write_all(&board.encode().to_le_bytes())
+	}
+
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+
+	pub fn into_inner(self) -> W {
+		self.inner
+	}
+}
+
+/**
+ * Read boards written by a `BoardWriter`, as an iterator.
+ *
+ * A record that does not decode yields an `InvalidData` error
+ * wrapping a `DecodeError`, and a truncated last record yields an
+ * `UnexpectedEof` error. Reading stops after the first error.
+ */
+pub struct BoardReader<R: Read> {
+	inner: R,
+	done: bool,
+}
+
+impl<R: Read> BoardReader<R> {
+	pub fn new(inner: R) -> Self {
+		BoardReader { inner, done: false }
+	}
+
+	fn read(&mut self) -> io::Result<Option<Board>> {
+		let mut bytes = [0; 8];
+		let mut read = 0;
+		while read < bytes.len() {
+			match self.inner.read(&mut bytes[read..]) {
+				Ok(0) if read == 0 => return Ok(None),
+				Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+				Ok(count) => read += count,
+				Err(error) if error.kind() == io::ErrorKind::Interrupted => (),
+				Err(error) => return Err(error),
+			}
+		}
+		Board::decode(u64::from_le_bytes(bytes))
+			.map(Some)
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+	}
+}
+
+impl<R: Read> Iterator for BoardReader<R> {
+	type Item = io::Result<Board>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+		let result = self.read();
+		self.done = !matches!(result, Ok(Some(_)));
+		result.transpose()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_encode_decode() {
+		use rand::SeedableRng;
+		use rand::rngs::StdRng;
+		use rand::seq::IteratorRandom;
+
+		let mut rng = StdRng::seed_from_u64(42);
+		for _ in 0..20 {
+			let mut board = Board::random_empty(&mut rng);
+			loop {
+				let code = board.encode();
+				assert_eq!(code & UNUSED_MASK, 0);
+				assert_eq!(Board::decode(code), Ok(board), "for {}", board.fen());
+				let Some(mov) = board.possible_moves().choose(&mut rng) else {
+					break;
+				};
+				board.play(mov).unwrap();
+			}
+		}
+		let board = Board::from_fen("1wbw/2b/1ww// 03478bcd s 1").unwrap();
+		assert_eq!(Board::decode(board.encode()), Ok(board));
+	}
+
+	#[test]
+	fn test_decode_errors() {
+		assert_eq!(
+			Board::decode(SQUARES_LIMIT),
+			Err(DecodeError::Malformed(SQUARES_LIMIT))
+		);
+		assert_eq!(Board::decode(1 << 63), Err(DecodeError::Malformed(1 << 63)));
+		// No tile for the current player.
+		assert_eq!(
+			Board::decode(0),
+			Err(DecodeError::Invalid(vec![Violation::TileDistribution]))
+		);
+	}
+
+	#[test]
+	fn test_encode_ply_out_of_range() {
+		let tiles = Board::empty().current_player.tiles();
+		let board = Board::from_parts(0, 0, tiles, Side::First, 0b100010);
+		let code = encode(&board);
+		assert_eq!(code & (SIDE_BIT | UNUSED_MASK), 0);
+		assert_eq!(
+			Board::decode(code),
+			Err(DecodeError::Invalid(vec![Violation::PlyOutOfRange(2)]))
+		);
+	}
+
+	#[test]
+	fn test_reader_and_writer() {
+		let boards = [
+			Board::empty(),
+			Board::from_fen("1wbw/2b/1ww// 03478bcd").unwrap(),
+		];
+		let mut writer = BoardWriter::new(Vec::new());
+		for board in &boards {
+			writer.write(board).unwrap();
+		}
+		let bytes = writer.into_inner();
+		assert_eq!(bytes.len(), 16);
+
+		let read: Vec<Board> = BoardReader::new(bytes.as_slice())
+			.collect::<io::Result<_>>()
+			.unwrap();
+		assert_eq!(read, boards);
+
+		let mut truncated = BoardReader::new(&bytes[..12]);
+		assert!(truncated.next().unwrap().is_ok());
+		assert_eq!(
+			truncated.next().unwrap().unwrap_err().kind(),
+			io::ErrorKind::UnexpectedEof
+		);
+		assert!(truncated.next().is_none());
+	}
+}
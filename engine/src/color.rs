@@ -18,8 +18,8 @@ impl std::ops::Not for Color {
 impl std::fmt::Display for Color {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
-			Color::Black => write!(f, "\x1b[31m"),
-			Color::White => write!(f, "\x1b[30m"),
+			Color::Black => write!(f, "black"),
+			Color::White => write!(f, "white"),
 		}
 	}
 }
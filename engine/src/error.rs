@@ -0,0 +1,225 @@
+use crate::mov::Move;
+
+/// Error returned when parsing a FEN or a move.
+///
+/// Each variant tells which part of the input failed, the byte
+/// offset of the faulty character in the parsed string, and why.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ParseError {
+	/// The board part of a FEN, e.g. `1wbw/2b/1ww/5/5`.
+	Board { offset: usize, kind: ParseErrorKind },
+	/// The tiles part of a FEN, e.g. `03478bcd`.
+	Tiles { offset: usize, kind: ParseErrorKind },
+	/// A move, e.g. `bd2`.
+	Move { offset: usize, kind: ParseErrorKind },
+	/// The side and ply counter of an extended FEN, e.g. `f 6`.
+	Turn { offset: usize, kind: ParseErrorKind },
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ParseErrorKind {
+	/// This character is not allowed here.
+	InvalidCharacter(char),
+	/// The input ended before this part was complete.
+	UnexpectedEnd,
+	/// The board has this many rows rather than 5.
+	RowCount(usize),
+	/// A row reaches this many columns, more than 5.
+	ColumnCount(usize),
+	/// This tile is listed twice.
+	DuplicateTile(u8),
+	/// There are this many tiles rather than 8.
+	TileCount(usize),
+	/// This number does not fit, e.g. a ply counter over the number
+	/// of pieces.
+	TooLarge,
+	/// The side to move does not match the parity of the ply counter.
+	SideMismatch,
+}
+
+impl ParseError {
+	pub const fn offset(&self) -> usize {
+		match self {
+			ParseError::Board { offset, .. }
+			| ParseError::Tiles { offset, .. }
+			| ParseError::Move { offset, .. }
+			| ParseError::Turn { offset, .. } => *offset,
+		}
+	}
+
+	pub const fn kind(&self) -> ParseErrorKind {
+		match self {
+			ParseError::Board { kind, .. }
+			| ParseError::Tiles { kind, .. }
+			| ParseError::Move { kind, .. }
+			| ParseError::Turn { kind, .. } => *kind,
+		}
+	}
+
+	/// Name of the part that failed, as used in messages.
+	pub const fn part(&self) -> &'static str {
+		match self {
+			ParseError::Board { .. } => "board",
+			ParseError::Tiles { .. } => "tiles",
+			ParseError::Move { .. } => "move",
+			ParseError::Turn { .. } => "turn",
+		}
+	}
+}
+
+impl std::fmt::Display for ParseErrorKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ParseErrorKind::InvalidCharacter(chr) => write!(f, "invalid character {:?}", chr),
+			ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+			ParseErrorKind::RowCount(count) => write!(f, "expected 5 rows, got {}", count),
+			ParseErrorKind::ColumnCount(count) => {
+				write!(f, "expected at most 5 columns, got {}", count)
+			}
+			ParseErrorKind::DuplicateTile(tile) => write!(f, "duplicate tile {:x}", tile),
+			ParseErrorKind::TileCount(count) => write!(f, "expected 8 tiles, got {}", count),
+			ParseErrorKind::TooLarge => write!(f, "number too large"),
+			ParseErrorKind::SideMismatch => write!(f, "side does not match the ply counter"),
+		}
+	}
+}
+
+impl std::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}: {} (at byte {})",
+			self.part(),
+			self.kind(),
+			self.offset()
+		)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// Error returned when playing a move that is not allowed.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct IllegalMove {
+	pub mov: Move,
+	pub kind: IllegalMoveKind,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IllegalMoveKind {
+	/// The square is not on the board.
+	OutOfBounds,
+	/// The square already holds a piece.
+	Occupied,
+	/// The move completes this tile twice at once.
+	DuplicateTile(u8),
+	/// The move completes this tile, which is already on the board.
+	AlreadyPlayed(u8),
+	/// The game is over, see `Game::play`.
+	GameOver,
+}
+
+impl std::fmt::Display for IllegalMoveKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			IllegalMoveKind::OutOfBounds => write!(f, "square is out of the board"),
+			IllegalMoveKind::Occupied => write!(f, "square is occupied"),
+			IllegalMoveKind::DuplicateTile(tile) => {
+				write!(f, "tile {:x} would be completed twice", tile)
+			}
+			IllegalMoveKind::AlreadyPlayed(tile) => {
+				write!(f, "tile {:x} is already on the board", tile)
+			}
+			IllegalMoveKind::GameOver => write!(f, "the game is over"),
+		}
+	}
+}
+
+impl std::fmt::Display for IllegalMove {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "illegal move {}: {}", self.mov, self.kind)
+	}
+}
+
+impl std::error::Error for IllegalMove {}
+
+/// Error returned when picking a tile during a draft, see `Setup`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IllegalPick {
+	/// Tiles are numbered from 0 to 15.
+	OutOfRange(u8),
+	/// This tile is already owned by a player.
+	AlreadyPicked(u8),
+	/// Every tile is owned, or tiles are not drafted.
+	Complete,
+}
+
+impl std::fmt::Display for IllegalPick {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			IllegalPick::OutOfRange(tile) => write!(f, "there is no tile {}", tile),
+			IllegalPick::AlreadyPicked(tile) => write!(f, "tile {:x} is already picked", tile),
+			IllegalPick::Complete => write!(f, "no tile is left to pick"),
+		}
+	}
+}
+
+impl std::error::Error for IllegalPick {}
+
+/// Error returned when building a `TileSet` from a list of tiles.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TileError {
+	/// Tiles are numbered from 0 to 15.
+	OutOfRange(u8),
+	/// This tile is listed more than once.
+	Duplicate(u8),
+}
+
+impl std::fmt::Display for TileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			TileError::OutOfRange(tile) => write!(f, "there is no tile {}", tile),
+			TileError::Duplicate(tile) => write!(f, "tile {:x} is listed twice", tile),
+		}
+	}
+}
+
+impl std::error::Error for TileError {}
+
+/// A broken invariant of a `Board`, see `Board::validate`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Violation {
+	/// Pieces outside of the 5x5 area, as a 7x7 mask.
+	OutOfBounds(u64),
+	/// Black pieces on empty squares, as a 7x7 mask.
+	BlackOnEmptySquare(u64),
+	/// This tile is completed more than once on the board.
+	DuplicateTile(u8),
+	/// Played tiles differ from the tiles completed on the board.
+	PlayedTilesMismatch,
+	/// Players do not own 8 different tiles each.
+	TileDistribution,
+	/// The hash does not match the position.
+	StaleHash,
+	/// The first player should move on even plies only.
+	SideMismatch,
+	/// More moves were played than there are pieces on the board.
+	PlyOutOfRange(u8),
+}
+
+impl std::fmt::Display for Violation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Violation::OutOfBounds(mask) => write!(f, "pieces out of the board ({:#x})", mask),
+			Violation::BlackOnEmptySquare(mask) => {
+				write!(f, "black pieces on empty squares ({:#x})", mask)
+			}
+			Violation::DuplicateTile(tile) => write!(f, "tile {:x} is completed twice", tile),
+			Violation::PlayedTilesMismatch => write!(f, "played tiles do not match the board"),
+			Violation::TileDistribution => write!(f, "players must own 8 different tiles each"),
+			Violation::StaleHash => write!(f, "hash does not match the position"),
+			Violation::SideMismatch => write!(f, "side to move does not match the ply"),
+			Violation::PlyOutOfRange(ply) => write!(f, "ply {} is over the number of pieces", ply),
+		}
+	}
+}
@@ -1,76 +1,363 @@
 use crate::board::Board;
+use crate::error::{IllegalMove, IllegalMoveKind};
 use crate::mov::Move;
-use std::convert::TryFrom;
+use crate::outcome::MoveOutcome;
+use crate::player::Side;
+use crate::record::{GameRecord, ReplayError};
 
+/**
+ * A game being played, from a starting position, with its move
+ * history and the moves that were undone and may be redone.
+ *
+ * `Game` does no I/O: frontends read moves, call `play`, `undo` and
+ * `redo`, and draw `board()`. Callbacks registered with `on_event`
+ * are told about every change, whoever made it.
+ *
+ * ```
+ * use ascacou::{Board, Game, Move};
+ *
+ * let mut game = Game::new(Board::empty());
+ * game.play(Move::black(0, 0)).unwrap();
+ * game.play(Move::white(1, 0)).unwrap();
+ * assert_eq!(game.undo(), Some(Move::white(1, 0)));
+ * assert_eq!(game.moves(), &[Move::black(0, 0)]);
+ * assert!(game.redo().is_some());
+ * assert_eq!(game.board().fen(), "bw//// 01234567");
+ * ```
+ */
 pub struct Game {
-	board: Board,
+	/// Every position, starting with the initial one.
+	boards: Vec<Board>,
+	moves: Vec<Move>,
+	/// Undone moves, the next one to redo being last.
+	undone: Vec<Move>,
+	players: [PlayerInfo; 2],
+	callbacks: Vec<Callback>,
 }
 
-enum Action {
-	Move(Move),
-	Rewind,
-	Quit,
+type Callback = Box<dyn FnMut(&GameEvent)>;
+
+/// Who plays a side, for display.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PlayerInfo {
+	pub name: String,
+	/// Whether moves are read from someone rather than computed.
+	pub human: bool,
+}
+
+/// Final score of a game, see `Game::result`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct GameResult {
+	/// Score of the first player, the second player scoring the
+	/// opposite.
+	pub score: i8,
+}
+
+/// A change of a `Game`, given to the callbacks of `Game::on_event`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum GameEvent {
+	Played(MoveOutcome),
+	Undone(Move),
+	Redone(MoveOutcome),
+	/// Sent after the move ending the game.
+	Ended(GameResult),
+}
+
+impl GameResult {
+	/// `None` for a draw.
+	pub fn winner(&self) -> Option<Side> {
+		match self.score {
+			0 => None,
+			score if score > 0 => Some(Side::First),
+			_ => Some(Side::Second),
+		}
+	}
+
+	/// Score of `side`.
+	pub fn score_for(&self, side: Side) -> i8 {
+		match side {
+			Side::First => self.score,
+			Side::Second => -self.score,
+		}
+	}
+}
+
+impl std::fmt::Display for GameResult {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.winner() {
+			None => write!(f, "draw"),
+			Some(Side::First) => write!(f, "first player wins by {}", self.score),
+			Some(Side::Second) => write!(f, "second player wins by {}", -self.score),
+		}
+	}
 }
 
 impl Game {
-	pub fn run_new(from_board: Option<Board>) {
-		let mut game = Game {
-			board: from_board.unwrap_or(Board::empty()),
-		};
-
-		game.run();
-	}
-
-	fn run(&mut self) {
-		let mut prev = vec![];
-		loop {
-			println!("\x1bc\x1b[3J"); /* clear screen */
-			println!("{}", self.board.for_console());
-
-			if self.board.is_terminal() {
-				println!("Game over!");
-				break;
-			}
-			println!(
-				"Possible moves: {}",
-				self.board
-					.possible_moves()
-					.map(String::from)
-					.collect::<Vec<String>>()
-					.join(", ")
-			);
-			println!("\nYour move (ba1 = black to the first row/col), [r]ewind or [q]uit:");
-
-			let action = self.read_move();
-
-			match action {
-				Action::Rewind => {
-					if let Some(prev_board) = prev.pop() {
-						self.board = prev_board;
-					}
-				}
-				Action::Move(mov) => {
-					prev.push(self.board.clone());
-					self.board = self.board.next(&mov).unwrap();
-				}
-				Action::Quit => {
-					break;
-				}
-			}
+	/// A game starting from `board`, which should be valid, see
+	/// `Board::validate`.
+	pub fn new(board: Board) -> Game {
+		debug_assert_eq!(board.validate(), Ok(()));
+		Game {
+			boards: vec![board],
+			moves: Vec::new(),
+			undone: Vec::new(),
+			players: [
+				PlayerInfo {
+					name: "First player".to_string(),
+					human: true,
+				},
+				PlayerInfo {
+					name: "Second player".to_string(),
+					human: true,
+				},
+			],
+			callbacks: Vec::new(),
+		}
+	}
+
+	/// A game at the end of `record`.
+	pub fn from_record(record: &GameRecord) -> Result<Game, ReplayError> {
+		let boards = record.replay()?;
+		Ok(Game {
+			boards,
+			moves: record.moves().to_vec(),
+			..Game::new(record.initial_board())
+		})
+	}
+
+	/// The current position.
+	pub fn board(&self) -> &Board {
+		self.boards.last().expect("a game has a position")
+	}
+
+	pub fn initial_board(&self) -> &Board {
+		&self.boards[0]
+	}
+
+	/// Positions from the initial one to the current one.
+	pub fn boards(&self) -> &[Board] {
+		&self.boards
+	}
+
+	/// Moves played from the initial position, undone moves excluded.
+	pub fn moves(&self) -> &[Move] {
+		&self.moves
+	}
+
+	/// Moves that `redo` would play again, next one first.
+	pub fn undone_moves(&self) -> impl Iterator<Item = &Move> {
+		self.undone.iter().rev()
+	}
+
+	/// The moves of the game, when it started from an empty board.
+	pub fn record(&self) -> Option<GameRecord> {
+		let mut record = GameRecord::new(self.initial_board())?;
+		for mov in &self.moves {
+			record.push(*mov);
 		}
+		Some(record)
+	}
+
+	pub fn player(&self, side: Side) -> &PlayerInfo {
+		&self.players[side as usize]
+	}
+
+	pub fn set_player(&mut self, side: Side, player: PlayerInfo) {
+		self.players[side as usize] = player;
 	}
 
-	fn read_move(&self) -> Action {
-		let mut mov_str = String::new();
-		while let None = std::io::stdin().read_line(&mut mov_str).ok() {}
-		mov_str = mov_str.trim().to_lowercase();
-		match mov_str.as_str() {
-			"r" | "rewind" => Action::Rewind,
-			"q" | "quit" => Action::Quit,
-			_ => match Move::try_from(mov_str) {
-				Ok(mov) if self.board.possible_moves().any(|m| m == mov) => Action::Move(mov),
-				_ => self.read_move(),
+	/// Whose turn it is.
+	pub fn side(&self) -> Side {
+		self.board().side()
+	}
+
+	pub fn is_over(&self) -> bool {
+		self.board().is_terminal()
+	}
+
+	/// The final score, once the game is over.
+	pub fn result(&self) -> Option<GameResult> {
+		if !self.is_over() {
+			return None;
+		}
+		let score = self.board().current_score();
+		Some(GameResult {
+			score: match self.side() {
+				Side::First => score,
+				Side::Second => -score,
 			},
+		})
+	}
+
+	/// Call `callback` after every change of the game.
+	pub fn on_event(&mut self, callback: impl FnMut(&GameEvent) + 'static) {
+		self.callbacks.push(Box::new(callback));
+	}
+
+	/// Play a move, forgetting undone moves.
+	pub fn play(&mut self, mov: Move) -> Result<MoveOutcome, IllegalMove> {
+		let outcome = self.push(mov)?;
+		self.undone.clear();
+		self.emit(GameEvent::Played(outcome.clone()));
+		self.emit_end();
+		Ok(outcome)
+	}
+
+	/// Take back the last move.
+	pub fn undo(&mut self) -> Option<Move> {
+		let mov = self.moves.pop()?;
+		self.boards.pop();
+		self.undone.push(mov);
+		self.emit(GameEvent::Undone(mov));
+		Some(mov)
+	}
+
+	/// Play the last undone move again.
+	pub fn redo(&mut self) -> Option<MoveOutcome> {
+		let mov = self.undone.pop()?;
+		let outcome = self.push(mov).expect("undone moves are legal");
+		self.emit(GameEvent::Redone(outcome.clone()));
+		self.emit_end();
+		Some(outcome)
+	}
+
+	pub fn can_undo(&self) -> bool {
+		!self.moves.is_empty()
+	}
+
+	pub fn can_redo(&self) -> bool {
+		!self.undone.is_empty()
+	}
+
+	fn push(&mut self, mov: Move) -> Result<MoveOutcome, IllegalMove> {
+		if self.is_over() {
+			return Err(IllegalMove {
+				mov,
+				kind: IllegalMoveKind::GameOver,
+			});
+		}
+		let outcome = self.board().apply(&mov)?;
+		self.boards.push(outcome.board);
+		self.moves.push(mov);
+		Ok(outcome)
+	}
+
+	fn emit(&mut self, event: GameEvent) {
+		for callback in &mut self.callbacks {
+			callback(&event);
+		}
+	}
+
+	fn emit_end(&mut self) {
+		if let Some(result) = self.result() {
+			self.emit(GameEvent::Ended(result));
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+	use std::rc::Rc;
+
+	#[test]
+	fn test_undo_redo() {
+		let mut game = Game::new(Board::empty());
+		assert!(!game.can_undo());
+		assert_eq!(game.undo(), None);
+		game.play(Move::black(0, 0)).unwrap();
+		game.play(Move::white(1, 0)).unwrap();
+		let after_two = *game.board();
+
+		assert_eq!(game.undo(), Some(Move::white(1, 0)));
+		assert_eq!(game.undo(), Some(Move::black(0, 0)));
+		assert_eq!(game.board(), &Board::empty());
+		assert_eq!(
+			game.undone_moves().collect::<Vec<_>>(),
+			[&Move::black(0, 0), &Move::white(1, 0)]
+		);
+		game.redo().unwrap();
+		game.redo().unwrap();
+		assert_eq!(game.board(), &after_two);
+		assert!(game.redo().is_none());
+
+		game.undo();
+		game.play(Move::black(2, 2)).unwrap();
+		assert!(!game.can_redo());
+		assert_eq!(game.boards().len(), 3);
+	}
+
+	#[test]
+	fn test_illegal_moves() {
+		let mut game = Game::new(Board::empty());
+		game.play(Move::black(0, 0)).unwrap();
+		assert_eq!(
+			game.play(Move::white(0, 0)).unwrap_err().kind,
+			IllegalMoveKind::Occupied
+		);
+		assert_eq!(game.moves().len(), 1);
+
+		let mut board = Board::empty();
+		while !board.is_terminal() {
+			let mov = board.possible_moves().next().unwrap();
+			board.play(mov).unwrap();
+		}
+		let mut game = Game::new(board);
+		assert_eq!(
+			game.play(Move::black(0, 0)).unwrap_err().kind,
+			IllegalMoveKind::GameOver
+		);
+	}
+
+	#[test]
+	fn test_record_and_result() {
+		let record = GameRecord::parse("[01234567] c2 B3 B1 C3 c1 D1").unwrap();
+		let game = Game::from_record(&record).unwrap();
+		assert_eq!(game.moves().len(), 6);
+		assert_eq!(game.record(), Some(record.clone()));
+		assert_eq!(game.board(), &record.final_board().unwrap());
+		assert_eq!(game.result(), None);
+
+		let game = Game::new(Board::from_fen("b//// 01234567").unwrap());
+		assert_eq!(game.record(), None);
+
+		let result = GameResult { score: -2 };
+		assert_eq!(result.winner(), Some(Side::Second));
+		assert_eq!(result.score_for(Side::Second), 2);
+		assert_eq!(result.to_string(), "second player wins by 2");
+	}
+
+	#[test]
+	fn test_events() {
+		use rand::SeedableRng;
+		use rand::rngs::StdRng;
+		use rand::seq::IteratorRandom;
+
+		let events = Rc::new(RefCell::new(Vec::new()));
+		let mut game = Game::new(Board::empty());
+		let recorded = events.clone();
+		game.on_event(move |event| recorded.borrow_mut().push(event.clone()));
+
+		let mut rng = StdRng::seed_from_u64(7);
+		while !game.is_over() {
+			let mov = game.board().possible_moves().choose(&mut rng).unwrap();
+			game.play(mov).unwrap();
+		}
+		let result = game.result().expect("game is over");
+		game.undo();
+		game.redo();
+
+		let events = events.borrow();
+		let count = events.len();
+		assert_eq!(count, game.moves().len() + 4);
+		assert!(matches!(events[0], GameEvent::Played(_)));
+		assert_eq!(events[count - 4], GameEvent::Ended(result));
+		assert_eq!(
+			events[count - 3],
+			GameEvent::Undone(*game.moves().last().unwrap())
+		);
+		assert!(matches!(events[count - 2], GameEvent::Redone(_)));
+		assert_eq!(events[count - 1], GameEvent::Ended(result));
+	}
+}
@@ -1,19 +1,50 @@
 #![feature(gen_blocks)]
 #![feature(test)]
+// We use hard tabs (see rustfmt.toml) and 7 bits groups for 7x7 masks.
+#![allow(clippy::tabs_in_doc_comments, clippy::unusual_byte_groupings)]
 
 extern crate test;
 
 pub(crate) mod board;
+pub(crate) mod codec;
 pub(crate) mod color;
+pub(crate) mod error;
 pub(crate) mod game;
 pub(crate) mod mov;
+pub(crate) mod movegen;
+pub(crate) mod outcome;
+pub(crate) mod perft;
 pub(crate) mod player;
+pub(crate) mod protocol;
+pub(crate) mod record;
+pub(crate) mod render;
+#[cfg(feature = "serde")]
+pub(crate) mod serialize;
+pub(crate) mod setup;
+pub(crate) mod symmetry;
 pub(crate) mod tileset;
+pub(crate) mod window;
+pub(crate) mod zobrist;
 
-pub use board::Board;
+pub use board::{Board, Undo};
+pub use codec::{BoardReader, BoardWriter, DecodeError};
 pub use color::Color;
-pub use game::Game;
+pub use error::{
+	IllegalMove, IllegalMoveKind, IllegalPick, ParseError, ParseErrorKind, TileError, Violation,
+};
+pub use game::{Game, GameEvent, GameResult, PlayerInfo};
 pub use mov::Move;
+pub use outcome::MoveOutcome;
+pub use player::{Player, Side};
+pub use protocol::{Engine, EngineOption, Info, Limits, Score, Search, serve};
+pub use record::{GameRecord, ReplayError};
+pub use render::{Ansi, Ascii, RENDERER_NAMES, Renderer, Svg, Unicode, renderer};
+#[cfg(feature = "serde")]
+pub use serialize::StructuredBoard;
+pub use setup::{Setup, SetupMethod};
+pub use symmetry::Symmetry;
+pub use tileset::TileSet;
+pub use window::Window;
 
 #[doc = include_str!("../../docs/Game-Analysis.md")]
 #[cfg(doctest)]
@@ -1,4 +1,5 @@
 use crate::color::Color;
+use crate::error::{ParseError, ParseErrorKind::*};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct Move {
@@ -88,46 +89,52 @@ impl std::ops::Not for Move {
  * 3. A digit, representing the y axis.
  *
  * Some valid moves: `wA1`, `Be5`, `bd2`.
- * Some invalid moves: `w1a`, `wf5`, `bd6`.
+ * Some invalid moves: `w1a`, `wf5`, `bd6`, `bd2 `.
  */
 impl std::convert::TryFrom<&str> for Move {
-	type Error = &'static str;
+	type Error = ParseError;
 
 	fn try_from(s: &str) -> Result<Move, Self::Error> {
-		let mut chars = s.chars();
+		let mut chars = s.char_indices();
+		let error = |offset, kind| ParseError::Move { offset, kind };
+		let unexpected_end = error(s.len(), UnexpectedEnd);
 
 		let color = match chars.next() {
-			Some('b' | 'B') => Color::Black,
-			Some('w' | 'W') => Color::White,
-			Some(_) => return Err("wrong color"),
-			None => return Err("missing information"),
+			Some((_, 'b' | 'B')) => Color::Black,
+			Some((_, 'w' | 'W')) => Color::White,
+			Some((offset, chr)) => return Err(error(offset, InvalidCharacter(chr))),
+			None => return Err(unexpected_end),
 		};
 
 		let x = match chars.next() {
-			Some('a' | 'A') => 0,
-			Some('b' | 'B') => 1,
-			Some('c' | 'C') => 2,
-			Some('d' | 'D') => 3,
-			Some('e' | 'E') => 4,
-			Some(_) => return Err("wrong x"),
-			None => return Err("missing information"),
+			Some((_, 'a' | 'A')) => 0,
+			Some((_, 'b' | 'B')) => 1,
+			Some((_, 'c' | 'C')) => 2,
+			Some((_, 'd' | 'D')) => 3,
+			Some((_, 'e' | 'E')) => 4,
+			Some((offset, chr)) => return Err(error(offset, InvalidCharacter(chr))),
+			None => return Err(unexpected_end),
 		};
 
 		let y = match chars.next() {
-			Some('1') => 0,
-			Some('2') => 1,
-			Some('3') => 2,
-			Some('4') => 3,
-			Some('5') => 4,
-			Some(_) => return Err("wrong y"),
-			None => return Err("missing information"),
+			Some((_, '1')) => 0,
+			Some((_, '2')) => 1,
+			Some((_, '3')) => 2,
+			Some((_, '4')) => 3,
+			Some((_, '5')) => 4,
+			Some((offset, chr)) => return Err(error(offset, InvalidCharacter(chr))),
+			None => return Err(unexpected_end),
 		};
 
+		if let Some((offset, chr)) = chars.next() {
+			return Err(error(offset, InvalidCharacter(chr)));
+		}
+
 		Ok(Move::new(x, y, color))
 	}
 }
 impl std::convert::TryFrom<String> for Move {
-	type Error = &'static str;
+	type Error = ParseError;
 	fn try_from(input: String) -> Result<Self, Self::Error> {
 		Move::try_from(input.as_ref())
 	}
@@ -184,6 +191,26 @@ mod tests {
 		assert_eq!(Move::try_from("Ba1"), Ok(Move::new(0, 0, Color::Black)))
 	}
 
+	#[test]
+	fn test_try_from_string_errors() {
+		for (input, offset, kind) in [
+			("", 0, UnexpectedEnd),
+			("b", 1, UnexpectedEnd),
+			("x1a", 0, InvalidCharacter('x')),
+			("w1a", 1, InvalidCharacter('1')),
+			("wf5", 1, InvalidCharacter('f')),
+			("bd6", 2, InvalidCharacter('6')),
+			("bd2 ", 3, InvalidCharacter(' ')),
+		] {
+			assert_eq!(
+				Move::try_from(input),
+				Err(ParseError::Move { offset, kind }),
+				"for input {:?}",
+				input
+			);
+		}
+	}
+
 	#[test]
 	fn test_from_mask() {
 		for (x, y, mask) in [
@@ -200,14 +227,14 @@ mod tests {
 			let mov_white = Move::from_mask(mask, Color::White);
 			assert_eq!(
 				mov_black,
-				Move::black(x as u8, y as u8),
+				Move::black(x, y),
 				"position ({}, {}) is incorrect",
 				x,
 				y
 			);
 			assert_eq!(
 				mov_white,
-				Move::white(x as u8, y as u8),
+				Move::white(x, y),
 				"position ({}, {}) is incorrect",
 				x,
 				y
@@ -0,0 +1,120 @@
+// Legal move generation, for every square at once.
+//
+// Most empty squares do not complete any window, both colors may
+// then be played there. Those squares are found with a few shifts of
+// the pieces mask. Remaining squares are checked one by one, using a
+// table of the windows holding each square.
+
+use crate::board::{BOARD_MASK, tile_at};
+use crate::mov::Move;
+use crate::tileset::TileSet;
+
+/// Windows holding a square, each given by the mask of its top
+/// left square and the mask of its three other squares.
+#[derive(Clone, Copy)]
+struct SquareWindows {
+	len: usize,
+	windows: [(u64, u64); 4],
+}
+
+/// Indexed by the bit position of a square in 7x7 masks, squares
+/// outside of the 5x5 area have no window.
+const SQUARE_WINDOWS: [SquareWindows; 64] = {
+	let mut table = [SquareWindows {
+		len: 0,
+		windows: [(0, 0); 4],
+	}; 64];
+	let mut y = 0;
+	while y < 5 {
+		let mut x = 0;
+		while x < 5 {
+			let square = Move::mask_at(x, y);
+			let entry = &mut table[square.trailing_zeros() as usize];
+			let mut window_y = if y == 0 { 0 } else { y - 1 };
+			while window_y <= y && window_y < 4 {
+				let mut window_x = if x == 0 { 0 } else { x - 1 };
+				while window_x <= x && window_x < 4 {
+					let top_left = Move::mask_at(window_x, window_y);
+					let window = top_left | top_left << 1 | top_left << 7 | top_left << 8;
+					entry.windows[entry.len] = (top_left, window & !square);
+					entry.len += 1;
+					window_x += 1;
+				}
+				window_y += 1;
+			}
+			x += 1;
+		}
+		y += 1;
+	}
+	table
+};
+
+/// Empty squares where a move would complete at least one window.
+const fn completing_squares(pieces_mask: u64) -> u64 {
+	let x = pieces_mask;
+	!x & BOARD_MASK
+		& ((x >> 1 & x >> 7 & x >> 8)
+			| (x << 1 & x >> 6 & x >> 7)
+			| (x >> 1 & x << 6 & x << 7)
+			| (x << 1 & x << 7 & x << 8))
+}
+
+/// Squares where a black and a white move are legal, see
+/// `Board::legal_moves`.
+pub(crate) fn legal_moves(pieces_mask: u64, black_mask: u64, played_tiles: TileSet) -> (u64, u64) {
+	let completing = completing_squares(pieces_mask);
+	let free = !pieces_mask & BOARD_MASK & !completing;
+	let (mut black_legal, mut white_legal) = (free, free);
+
+	let mut squares = completing;
+	while squares != 0 {
+		let square = squares & squares.wrapping_neg();
+		squares ^= square;
+
+		let entry = &SQUARE_WINDOWS[square.trailing_zeros() as usize];
+		// Tiles formed by each color, and whether one is formed twice.
+		let (mut black_tiles, mut white_tiles) = (0u16, 0u16);
+		let (mut black_dup, mut white_dup) = (false, false);
+		for &(top_left, others) in &entry.windows[..entry.len] {
+			if pieces_mask & others != others {
+				continue;
+			}
+			let black_tile = 1 << tile_at(&(black_mask | square), top_left);
+			let white_tile = 1 << tile_at(&black_mask, top_left);
+			black_dup |= black_tiles & black_tile != 0;
+			white_dup |= white_tiles & white_tile != 0;
+			black_tiles |= black_tile;
+			white_tiles |= white_tile;
+		}
+		if !black_dup && black_tiles & played_tiles.bits() == 0 {
+			black_legal |= square;
+		}
+		if !white_dup && white_tiles & played_tiles.bits() == 0 {
+			white_legal |= square;
+		}
+	}
+
+	(black_legal, white_legal)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_square_windows() {
+		let corner = &SQUARE_WINDOWS[Move::mask_at(0, 0).trailing_zeros() as usize];
+		assert_eq!(corner.len, 1);
+		let edge = &SQUARE_WINDOWS[Move::mask_at(2, 4).trailing_zeros() as usize];
+		assert_eq!(edge.len, 2);
+		let center = &SQUARE_WINDOWS[Move::mask_at(2, 2).trailing_zeros() as usize];
+		assert_eq!(center.len, 4);
+		for &(top_left, others) in &center.windows {
+			assert_eq!(others.count_ones(), 3);
+			assert_eq!(others & Move::mask_at(2, 2), 0);
+			assert!(top_left & (others | Move::mask_at(2, 2)) != 0);
+		}
+		let total: usize = SQUARE_WINDOWS.iter().map(|entry| entry.len).sum();
+		assert_eq!(total, 16 * 4);
+	}
+}
@@ -0,0 +1,15 @@
+use crate::board::Board;
+use crate::mov::Move;
+use crate::player::Side;
+
+/// What a move did, see `Board::apply`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct MoveOutcome {
+	pub mov: Move,
+	/// The position after the move.
+	pub board: Board,
+	/// Tiles completed by the move, with the side owning each.
+	pub tiles: Vec<(u8, Side)>,
+	/// Score change for the player who moved.
+	pub score_delta: i8,
+}
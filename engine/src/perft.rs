@@ -0,0 +1,158 @@
+// Perft, counting the positions reached after a given number of
+// moves. Counts for fixed positions are checked in `perft.txt`, any
+// change to move generation must keep them unchanged.
+
+use std::collections::HashSet;
+
+use crate::board::Board;
+use crate::mov::Move;
+
+impl Board {
+	/**
+	 * Number of move sequences of exactly `depth` moves from this
+	 * position. Games ending earlier are not counted, a position
+	 * where every tile is on the board being the end of the game.
+	 */
+	pub fn perft(&self, depth: u8) -> u64 {
+		let mut board = *self;
+		perft(&mut board, depth)
+	}
+
+	/// Perft of each legal move, to find which subtree differs from
+	/// a reference count.
+	pub fn perft_divide(&self, depth: u8) -> Vec<(Move, u64)> {
+		if depth == 0 || self.played_tiles.is_full() {
+			return vec![];
+		}
+		let mut board = *self;
+		let moves: Vec<Move> = self.possible_moves().collect();
+		moves
+			.into_iter()
+			.map(|mov| {
+				let undo = board.play(mov).expect("should play valid move");
+				let count = perft(&mut board, depth - 1);
+				board.undo(undo);
+				(mov, count)
+			})
+			.collect()
+	}
+
+	/// Number of different positions reached after exactly `depth`
+	/// moves, transpositions being counted once.
+	pub fn perft_unique(&self, depth: u8) -> u64 {
+		// Encoding is exact, unlike hashes.
+		let mut positions: HashSet<u64> = HashSet::from([self.encode()]);
+		for _ in 0..depth {
+			let mut next_positions = HashSet::new();
+			for code in positions {
+				let board = Board::decode(code).expect("encoded boards are valid");
+				if board.played_tiles.is_full() {
+					continue;
+				}
+				for mov in board.possible_moves() {
+					let next = board.next(&mov).expect("should play valid move");
+					next_positions.insert(next.encode());
+				}
+			}
+			positions = next_positions;
+		}
+		positions.len() as u64
+	}
+}
+
+fn perft(board: &mut Board, depth: u8) -> u64 {
+	if depth == 0 {
+		return 1;
+	}
+	if board.played_tiles.is_full() {
+		return 0;
+	}
+	let (black_legal, white_legal) = board.legal_moves();
+	if depth == 1 {
+		return (black_legal.count_ones() + white_legal.count_ones()) as u64;
+	}
+	let moves: Vec<Move> = board.possible_moves().collect();
+	moves
+		.into_iter()
+		.map(|mov| {
+			let undo = board.play(mov).expect("should play valid move");
+			let count = perft(board, depth - 1);
+			board.undo(undo);
+			count
+		})
+		.sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Reference counts, as `fen;depth;perft;unique` lines.
+	const REFERENCE: &str = include_str!("perft.txt");
+
+	fn reference() -> impl Iterator<Item = (Board, u8, u64, u64)> {
+		REFERENCE
+			.lines()
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(|line| {
+				let fields: Vec<&str> = line.split(';').collect();
+				(
+					Board::from_fen(fields[0]).unwrap(),
+					fields[1].parse().unwrap(),
+					fields[2].parse().unwrap(),
+					fields[3].parse().unwrap(),
+				)
+			})
+	}
+
+	/// Perft with moves checked one by one, independently of
+	/// `Board::legal_moves`.
+	fn naive_perft(board: &Board, depth: u8) -> u64 {
+		if depth == 0 {
+			return 1;
+		}
+		if board.played_tiles.is_full() {
+			return 0;
+		}
+		(0..5)
+			.flat_map(|x| (0..5).map(move |y| (x, y)))
+			.flat_map(|(x, y)| [Move::black(x, y), Move::white(x, y)])
+			.filter(|mov| board.is_move_possible(mov))
+			.map(|mov| naive_perft(&board.next(&mov).unwrap(), depth - 1))
+			.sum()
+	}
+
+	#[test]
+	fn test_reference_counts() {
+		for (board, depth, count, unique) in reference() {
+			assert_eq!(board.perft(depth), count, "perft {} of {}", depth, board);
+			assert_eq!(
+				board.perft_unique(depth),
+				unique,
+				"unique perft {} of {}",
+				depth,
+				board
+			);
+		}
+	}
+
+	#[test]
+	fn test_naive_perft() {
+		for (board, depth, count, _) in reference() {
+			if count < 50_000 {
+				assert_eq!(naive_perft(&board, depth), count, "for {}", board);
+			}
+		}
+	}
+
+	#[test]
+	fn test_perft_divide() {
+		let board = Board::from_fen("bw/1ww/1bb1w//b1ww 0367abce").unwrap();
+		let divide = board.perft_divide(2);
+		assert_eq!(divide.len() as u64, board.perft(1));
+		assert_eq!(
+			divide.iter().map(|(_, count)| count).sum::<u64>(),
+			board.perft(2)
+		);
+	}
+}
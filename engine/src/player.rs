@@ -1,95 +1,104 @@
 use crate::color::Color;
+use crate::error::Violation;
+use crate::render::{Ansi, Renderer};
 use crate::tileset::TileSet;
 use rand::Rng;
 use rand::seq::IteratorRandom;
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+/// Whether a player is the one who moved first in the game.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Side {
+	First,
+	Second,
+}
+
+impl Side {
+	/// The side to move after `ply` moves.
+	pub const fn from_ply(ply: u8) -> Side {
+		if ply.is_multiple_of(2) {
+			Side::First
+		} else {
+			Side::Second
+		}
+	}
+}
+
+impl std::ops::Not for Side {
+	type Output = Self;
+
+	fn not(self) -> Self::Output {
+		match self {
+			Side::First => Side::Second,
+			Side::Second => Side::First,
+		}
+	}
+}
+
+/// A player, owning 8 of the 16 tiles.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct Player {
 	tiles: TileSet,
-	// Used in heuristics.
-	pub favorite_color: Color,
+	favorite_color: Color,
 }
 
 impl Player {
+	/// A player owning `tiles`, which must be 8 different tiles.
+	pub fn new(tiles: TileSet) -> Result<Player, Violation> {
+		if tiles.is_distribution() {
+			Ok(Player::new_unchecked(tiles))
+		} else {
+			Err(Violation::TileDistribution)
+		}
+	}
+
+	/// The player owning `tiles`, and its opponent owning the others.
+	pub fn pair(tiles: TileSet) -> Result<(Player, Player), Violation> {
+		let player = Player::new(tiles)?;
+		Ok((player, Player::new_unchecked(!tiles)))
+	}
+
 	pub fn default_set() -> (Player, Player) {
-		(
-			Player::new([0, 1, 2, 3, 4, 5, 6, 7]),
-			Player::new([15, 14, 13, 12, 11, 10, 9, 8]),
-		)
+		// Tiles 0 to 7, and 8 to 15 for the opponent.
+		Player::pair_unchecked(TileSet::from_bits(0x00FF))
 	}
 
 	pub fn random_set<R: Rng>(rng: &mut R) -> (Player, Player) {
-		(0..16)
-			.choose_multiple(rng, 8)
-			.iter()
-			.cloned()
-			.collect::<TileSet>()
-			.into()
+		Player::pair_unchecked(TileSet::union_of((0..16).choose_multiple(rng, 8)))
 	}
 
-	fn new(tiles: impl Into<TileSet>) -> Player {
-		let ts = tiles.into();
+	/// Like `Player::pair`, for tiles that are known to be 8 different
+	/// ones or that are validated later, see `Board::validate`.
+	pub(crate) fn pair_unchecked(tiles: TileSet) -> (Player, Player) {
+		(Player::new_unchecked(tiles), Player::new_unchecked(!tiles))
+	}
+
+	fn new_unchecked(tiles: TileSet) -> Player {
 		Player {
-			tiles: ts,
-			favorite_color: ts.most_present_color(),
+			tiles,
+			favorite_color: tiles.most_present_color(),
 		}
 	}
 
+	pub fn tiles(&self) -> TileSet {
+		self.tiles
+	}
+
+	/// The color of most squares of the player's tiles, black on a
+	/// tie. Used in heuristics.
+	pub fn favorite_color(&self) -> Color {
+		self.favorite_color
+	}
+
 	pub fn has_tile(&self, tile: u8) -> bool {
 		self.tiles.has(tile)
 	}
 
 	pub fn fen_part(&self) -> String {
-		self.tiles
-			.into_iter()
-			.map(|tile| format!("{:x}", tile))
-			.collect::<String>()
+		self.tiles.to_string()
 	}
 
 	pub fn for_console(&self, played_tiles: &TileSet) -> String {
-		let mut str = String::with_capacity(209);
-		for tile in self.tiles {
-			str.push_str(&format!("  {: >2}  ", tile));
-		}
-		str.push('\n');
-		for y in 0..2 {
-			let mut first_tile = true;
-
-			for tile in self.tiles {
-				if first_tile {
-					first_tile = false;
-				} else {
-					str.push(' ')
-				}
-				if played_tiles.has(tile) {
-					str.push_str("\x1b[44m");
-				} else {
-					str.push_str("\x1b[47m");
-				}
-				for x in 0..2 {
-					// Tiles are counted horizontally starting at top left.
-					let position = 1 << x << 2 * y;
-
-					if tile & position != 0 {
-						str.push_str(format!(" {}●", Color::Black).as_str());
-					} else {
-						str.push_str(format!(" {}●", Color::White).as_str());
-					}
-				}
-				str.push_str(" \x1b[0m");
-			}
-			str.push('\n');
-		}
-		str
-	}
-}
-
-/// Given a valid list of tiles (8 different tiles, unchecked here)
-/// return two players: the first one is the current player, the
-/// second one is its opponent.
-impl From<TileSet> for (Player, Player) {
-	fn from(tileset: TileSet) -> (Player, Player) {
-		(Player::new(tileset), Player::new(!tileset))
+		Ansi.rack(self, *played_tiles)
 	}
 }
 
@@ -108,6 +117,23 @@ fn test_show_players() {
 
 #[test]
 fn test_fen_part() {
-	let player = Player::new([15, 14, 13, 12, 11, 10, 9, 8]);
+	let player = Player::new(TileSet::try_from([15, 14, 13, 12, 11, 10, 9, 8]).unwrap()).unwrap();
 	assert_eq!(player.fen_part(), "89abcdef");
 }
+
+#[test]
+fn test_new_rejects_invalid_sets() {
+	assert_eq!(
+		Player::new(TileSet::try_from([0, 1, 2, 3]).unwrap()),
+		Err(Violation::TileDistribution)
+	);
+	assert_eq!(
+		Player::new(TileSet::full()),
+		Err(Violation::TileDistribution)
+	);
+	let (player, opponent) =
+		Player::pair(TileSet::try_from([0, 1, 2, 3, 4, 5, 6, 7]).unwrap()).unwrap();
+	assert_eq!(opponent.tiles(), !player.tiles());
+	assert_eq!(player.favorite_color(), Color::White);
+	assert_eq!(opponent.favorite_color(), Color::Black);
+}
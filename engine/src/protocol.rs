@@ -0,0 +1,585 @@
+use crate::board::Board;
+use crate::mov::Move;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/**
+ * A text protocol for engines, modeled after UCI, so that GUIs and
+ * match runners can talk to any engine the same way. Commands are
+ * read one per line, on standard input:
+ *
+ * - `uci` asks for the engine name and its options, answered with
+ *   `id name <name>`, one `option name <name> type spin default <n>
+ *   min <n> max <n>` line per option, then `uciok`;
+ * - `isready`, answered with `readyok`;
+ * - `setoption name <name> value <n>`;
+ * - `ucinewgame`, before a position from another game;
+ * - `position fen <fen> [moves <move>...]` or
+ *   `position startpos [moves <move>...]`, the latter starting from
+ *   `Board::empty`;
+ * - `go [depth <n>] [movetime <ms>] [nodes <n>] [infinite]`, searching
+ *   the position until one of the limits is reached. Without any limit
+ *   the engine picks one from its options;
+ * - `stop`, ending the current search;
+ * - `quit`, ending the current search and the session.
+ *
+ * While searching, the engine prints lines such as
+ * `info depth 6 score tiles 2 nodes 16384 time 12 pv c2 B3`, then
+ * `bestmove <move>`, or `bestmove none` when there is no legal move.
+ * Errors are reported with `info string <message>`.
+ *
+ * Only `stop` and `quit` are handled during a search, other commands
+ * wait for it to end.
+ */
+pub trait Engine {
+	/// Name and version of the engine.
+	fn name(&self) -> String;
+
+	fn options(&self) -> Vec<EngineOption> {
+		vec![]
+	}
+
+	/// Called with the name of one of `options` and a value within
+	/// its bounds.
+	fn set_option(&mut self, _name: &str, _value: i64) {}
+
+	/// Forget anything learned from previous positions.
+	fn new_game(&mut self) {}
+
+	/// Best move on `board`, `None` when there is no legal move.
+	///
+	/// Engines should call `Search::should_stop` often, and still
+	/// return a move when stopped right away.
+	fn search(&mut self, board: &Board, search: &mut Search) -> Option<Move>;
+}
+
+/// An integer setting of an engine, a `spin` option in UCI terms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EngineOption {
+	pub name: &'static str,
+	pub default: i64,
+	pub min: i64,
+	pub max: i64,
+}
+
+impl fmt::Display for EngineOption {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"option name {} type spin default {} min {} max {}",
+			self.name, self.default, self.min, self.max
+		)
+	}
+}
+
+/// Limits of a search, given with `go`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+	pub depth: Option<u8>,
+	pub movetime: Option<Duration>,
+	pub nodes: Option<u64>,
+	/// Search until `stop`, or until the engine cannot do better.
+	pub infinite: bool,
+}
+
+impl Limits {
+	/// Whether `go` was given no limit, letting the engine pick one.
+	pub fn is_empty(&self) -> bool {
+		*self == Limits::default()
+	}
+}
+
+/// The arguments of `go`, e.g. `depth 4 movetime 100`.
+impl fmt::Display for Limits {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut words = vec![];
+		if let Some(depth) = self.depth {
+			words.push(format!("depth {}", depth));
+		}
+		if let Some(movetime) = self.movetime {
+			words.push(format!("movetime {}", movetime.as_millis()));
+		}
+		if let Some(nodes) = self.nodes {
+			words.push(format!("nodes {}", nodes));
+		}
+		if self.infinite {
+			words.push("infinite".to_string());
+		}
+		write!(f, "{}", words.join(" "))
+	}
+}
+
+/// Evaluation of a position for the player to move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Score {
+	/// Expected difference of tiles at the end of the game.
+	Tiles(i16),
+	/// Mean result of games played from the position, from -1 when
+	/// every game is lost to 1 when every game is won.
+	Mean(f64),
+}
+
+impl fmt::Display for Score {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Score::Tiles(tiles) => write!(f, "tiles {}", tiles),
+			Score::Mean(mean) => write!(f, "mean {:.3}", mean),
+		}
+	}
+}
+
+/// Progress of a search, printed as an `info` line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Info {
+	pub depth: u8,
+	pub score: Option<Score>,
+	pub nodes: u64,
+	pub time: Duration,
+	/// Principal variation, the best move followed by the expected
+	/// replies.
+	pub pv: Vec<Move>,
+}
+
+impl fmt::Display for Info {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "info depth {}", self.depth)?;
+		if let Some(score) = self.score {
+			write!(f, " score {}", score)?;
+		}
+		write!(f, " nodes {} time {}", self.nodes, self.time.as_millis())?;
+		if !self.pv.is_empty() {
+			write!(f, " pv")?;
+			for mov in &self.pv {
+				write!(f, " {}", mov)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A running search, given to `Engine::search`.
+pub struct Search<'a> {
+	pub limits: Limits,
+	start: Instant,
+	stopped: &'a dyn Fn() -> bool,
+	report: &'a mut dyn FnMut(Info),
+}
+
+impl<'a> Search<'a> {
+	/// A search starting now. `stopped` tells whether the search was
+	/// asked to end, and `report` receives `info` lines.
+	pub fn new(
+		limits: Limits,
+		stopped: &'a dyn Fn() -> bool,
+		report: &'a mut dyn FnMut(Info),
+	) -> Search<'a> {
+		Search {
+			limits,
+			start: Instant::now(),
+			stopped,
+			report,
+		}
+	}
+
+	pub fn elapsed(&self) -> Duration {
+		self.start.elapsed()
+	}
+
+	/// Whether the search should end after exploring `nodes` nodes,
+	/// either because it was stopped or because it reached its time
+	/// or nodes limit. The depth limit is left to the engine.
+	pub fn should_stop(&self, nodes: u64) -> bool {
+		(self.stopped)()
+			|| self
+				.limits
+				.movetime
+				.is_some_and(|movetime| self.elapsed() >= movetime)
+			|| self.limits.nodes.is_some_and(|limit| nodes >= limit)
+	}
+
+	pub fn report(&mut self, info: Info) {
+		(self.report)(info)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Command {
+	Uci,
+	IsReady,
+	NewGame,
+	SetOption {
+		name: String,
+		value: String,
+	},
+	/// The position after the given moves were played.
+	Position(Board),
+	Go(Limits),
+	Stop,
+	Quit,
+}
+
+impl FromStr for Command {
+	type Err = String;
+
+	fn from_str(line: &str) -> Result<Command, String> {
+		let mut words = line.split_whitespace();
+		let command = match words.next() {
+			Some("uci") => Command::Uci,
+			Some("isready") => Command::IsReady,
+			Some("ucinewgame") => Command::NewGame,
+			Some("setoption") => {
+				let words: Vec<&str> = words.collect();
+				let value_index = words.iter().position(|&word| word == "value");
+				match (words.first(), value_index) {
+					(Some(&"name"), Some(index)) if index > 1 => Command::SetOption {
+						name: words[1..index].join(" "),
+						value: words[index + 1..].join(" "),
+					},
+					_ => {
+						return Err(format!(
+							"expected setoption name <name> value <value>: {}",
+							line
+						));
+					}
+				}
+			}
+			Some("position") => Command::Position(parse_position(words)?),
+			Some("go") => Command::Go(parse_limits(words)?),
+			Some("stop") => Command::Stop,
+			Some("quit") => Command::Quit,
+			_ => return Err(format!("unknown command: {}", line)),
+		};
+		Ok(command)
+	}
+}
+
+fn parse_position<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<Board, String> {
+	let mut board = match words.next() {
+		Some("startpos") => Board::empty(),
+		Some("fen") => {
+			let fen: Vec<&str> = words.by_ref().take_while(|&word| word != "moves").collect();
+			let board = Board::from_fen(&fen.join(" ")).map_err(|error| error.to_string())?;
+			board.validate().map_err(|violations| {
+				violations
+					.iter()
+					.map(|violation| violation.to_string())
+					.collect::<Vec<String>>()
+					.join(", ")
+			})?;
+			// `take_while` already consumed the `moves` keyword.
+			return play_moves(board, words);
+		}
+		_ => return Err("expected position fen <fen> or position startpos".to_string()),
+	};
+	match words.next() {
+		Some("moves") => board = play_moves(board, words)?,
+		Some(word) => return Err(format!("expected moves, got {}", word)),
+		None => (),
+	}
+	Ok(board)
+}
+
+fn play_moves<'a>(mut board: Board, words: impl Iterator<Item = &'a str>) -> Result<Board, String> {
+	for word in words {
+		let mov = Move::try_from(word).map_err(|error| error.to_string())?;
+		board.play(mov).map_err(|error| error.to_string())?;
+	}
+	Ok(board)
+}
+
+fn parse_limits<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<Limits, String> {
+	fn value<'a, T: FromStr>(
+		name: &str,
+		words: &mut impl Iterator<Item = &'a str>,
+	) -> Result<T, String> {
+		words
+			.next()
+			.and_then(|word| word.parse().ok())
+			.ok_or_else(|| format!("expected a number after {}", name))
+	}
+
+	let mut limits = Limits::default();
+	while let Some(word) = words.next() {
+		match word {
+			"depth" => limits.depth = Some(value(word, &mut words)?),
+			"movetime" => limits.movetime = Some(Duration::from_millis(value(word, &mut words)?)),
+			"nodes" => limits.nodes = Some(value(word, &mut words)?),
+			"infinite" => limits.infinite = true,
+			_ => return Err(format!("unknown limit: {}", word)),
+		}
+	}
+	Ok(limits)
+}
+
+fn set_option(engine: &mut impl Engine, name: &str, value: &str) -> Result<(), String> {
+	let Some(option) = engine
+		.options()
+		.into_iter()
+		.find(|option| option.name.eq_ignore_ascii_case(name))
+	else {
+		return Err(format!("unknown option: {}", name));
+	};
+	match value.parse() {
+		Ok(value) if (option.min..=option.max).contains(&value) => {
+			engine.set_option(option.name, value);
+			Ok(())
+		}
+		_ => Err(format!(
+			"{} expects a number from {} to {}",
+			option.name, option.min, option.max
+		)),
+	}
+}
+
+/**
+ * Run `engine` with the protocol described on `Engine`, reading
+ * commands from `input` until `quit` or its end, and writing answers
+ * to `output`.
+ *
+ * Commands are read on another thread, so that `stop` can end a
+ * search. A `stop` ends every search requested before it, even if
+ * it did not start yet.
+ */
+pub fn serve(
+	engine: &mut impl Engine,
+	input: impl BufRead + Send + 'static,
+	mut output: impl Write,
+) -> io::Result<()> {
+	// Number of `go` commands that a `stop` or `quit` followed.
+	let stopped_searches = Arc::new(AtomicUsize::new(0));
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn({
+		let stopped_searches = stopped_searches.clone();
+		move || {
+			let mut searches = 0;
+			for line in input.lines() {
+				let Ok(line) = line else { break };
+				if line.trim().is_empty() {
+					continue;
+				}
+				let command = line.parse::<Command>();
+				match command {
+					Ok(Command::Go(_)) => searches += 1,
+					Ok(Command::Stop | Command::Quit) => {
+						stopped_searches.store(searches, Ordering::SeqCst)
+					}
+					_ => (),
+				}
+				if sender.send(command).is_err() {
+					break;
+				}
+			}
+		}
+	});
+
+	let mut board = Board::empty();
+	let mut searches = 0;
+	for command in receiver {
+		match command {
+			Err(error) => writeln!(output, "info string {}", error)?,
+			Ok(Command::Uci) => {
+				writeln!(output, "id name {}", engine.name())?;
+				for option in engine.options() {
+					writeln!(output, "{}", option)?;
+				}
+				writeln!(output, "uciok")?;
+			}
+			Ok(Command::IsReady) => writeln!(output, "readyok")?,
+			Ok(Command::NewGame) => engine.new_game(),
+			Ok(Command::SetOption { name, value }) => {
+				if let Err(error) = set_option(engine, &name, &value) {
+					writeln!(output, "info string {}", error)?;
+				}
+			}
+			Ok(Command::Position(position)) => board = position,
+			Ok(Command::Go(limits)) => {
+				searches += 1;
+				let stopped = || stopped_searches.load(Ordering::SeqCst) >= searches;
+				let mut report = |info: Info| {
+					// Write errors show up with the `bestmove` line.
+					let _ = writeln!(output, "{}", info).and_then(|()| output.flush());
+				};
+				let mov = engine.search(&board, &mut Search::new(limits, &stopped, &mut report));
+				match mov {
+					Some(mov) => writeln!(output, "bestmove {}", mov)?,
+					None => writeln!(output, "bestmove none")?,
+				}
+			}
+			Ok(Command::Stop) => (),
+			Ok(Command::Quit) => break,
+		}
+		output.flush()?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Plays the first legal move, searching one node per depth.
+	struct FirstMove {
+		depth: i64,
+	}
+
+	impl Engine for FirstMove {
+		fn name(&self) -> String {
+			"first move".to_string()
+		}
+
+		fn options(&self) -> Vec<EngineOption> {
+			vec![EngineOption {
+				name: "Depth",
+				default: 1,
+				min: 1,
+				max: 10,
+			}]
+		}
+
+		fn set_option(&mut self, _name: &str, value: i64) {
+			self.depth = value;
+		}
+
+		fn search(&mut self, board: &Board, search: &mut Search) -> Option<Move> {
+			let mov = board.possible_moves().next()?;
+			let depth = search.limits.depth.map_or(self.depth, i64::from);
+			let mut nodes = 0;
+			while nodes < depth && (search.limits.infinite || !search.should_stop(nodes as u64)) {
+				nodes += 1;
+				search.report(Info {
+					depth: nodes as u8,
+					score: Some(Score::Tiles(0)),
+					nodes: nodes as u64,
+					time: Duration::ZERO,
+					pv: vec![mov],
+				});
+			}
+			// An infinite search waits for `stop`.
+			while search.limits.infinite && !search.should_stop(nodes as u64) {
+				thread::yield_now();
+			}
+			Some(mov)
+		}
+	}
+
+	fn serve_lines(input: &str) -> Vec<String> {
+		let mut output = vec![];
+		let input = io::Cursor::new(input.as_bytes().to_vec());
+		serve(&mut FirstMove { depth: 1 }, input, &mut output).unwrap();
+		String::from_utf8(output)
+			.unwrap()
+			.lines()
+			.map(String::from)
+			.collect()
+	}
+
+	#[test]
+	fn test_parse() {
+		assert_eq!("uci".parse(), Ok(Command::Uci));
+		assert_eq!(
+			"setoption name Move Time value 200".parse(),
+			Ok(Command::SetOption {
+				name: "Move Time".to_string(),
+				value: "200".to_string()
+			})
+		);
+		assert_eq!(
+			"position fen //// 01234567 moves ba1 wb1".parse(),
+			Ok(Command::Position(
+				Board::from_fen("bw//// 01234567").unwrap()
+			))
+		);
+		assert_eq!(
+			"position fen bw//// 01234567".parse(),
+			Ok(Command::Position(
+				Board::from_fen("bw//// 01234567").unwrap()
+			))
+		);
+		assert_eq!(
+			"position startpos moves ba1".parse(),
+			Ok(Command::Position(
+				Board::empty().next(&Move::black(0, 0)).unwrap()
+			))
+		);
+		assert_eq!(
+			"go depth 4 movetime 100 nodes 1000".parse(),
+			Ok(Command::Go(Limits {
+				depth: Some(4),
+				movetime: Some(Duration::from_millis(100)),
+				nodes: Some(1000),
+				infinite: false,
+			}))
+		);
+		let limits = Limits {
+			movetime: Some(Duration::from_millis(100)),
+			nodes: Some(1000),
+			..Limits::default()
+		};
+		assert_eq!(limits.to_string(), "movetime 100 nodes 1000");
+		assert_eq!(format!("go {}", limits).parse(), Ok(Command::Go(limits)));
+		assert_eq!("go".parse(), Ok(Command::Go(Limits::default())));
+		assert!("go depth".parse::<Command>().is_err());
+		assert!(
+			"position startpos moves ba1 wa1"
+				.parse::<Command>()
+				.is_err()
+		);
+		assert!("position fen 6/ 01234567".parse::<Command>().is_err());
+		assert!("setoption value 3".parse::<Command>().is_err());
+		assert!("jump".parse::<Command>().is_err());
+	}
+
+	#[test]
+	fn test_serve() {
+		assert_eq!(
+			serve_lines(
+				"uci\nsetoption name depth value 2\nisready\n\
+				 position startpos moves ba1\ngo\nsetoption name Depth value 11\n"
+			),
+			vec![
+				"id name first move",
+				"option name Depth type spin default 1 min 1 max 10",
+				"uciok",
+				"readyok",
+				"info depth 1 score tiles 0 nodes 1 time 0 pv bb1",
+				"info depth 2 score tiles 0 nodes 2 time 0 pv bb1",
+				"bestmove bb1",
+				"info string Depth expects a number from 1 to 10",
+			]
+		);
+		let mut board = Board::empty();
+		while !board.is_terminal() {
+			let mov = board.possible_moves().next().unwrap();
+			board.play(mov).unwrap();
+		}
+		assert_eq!(
+			serve_lines(&format!(
+				"go nodes 1\nposition fen {}\ngo\nquit\nisready\n",
+				board.fen()
+			)),
+			vec![
+				"info depth 1 score tiles 0 nodes 1 time 0 pv ba1",
+				"bestmove ba1",
+				"bestmove none",
+			]
+		);
+	}
+
+	#[test]
+	fn test_stop() {
+		assert_eq!(
+			serve_lines("go infinite\nstop\nisready\n"),
+			vec![
+				"info depth 1 score tiles 0 nodes 1 time 0 pv ba1",
+				"bestmove ba1",
+				"readyok"
+			]
+		);
+	}
+}
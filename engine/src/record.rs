@@ -0,0 +1,294 @@
+use crate::board::Board;
+use crate::color::Color;
+use crate::error::{IllegalMove, ParseError, ParseErrorKind::*};
+use crate::mov::Move;
+use crate::player::Side;
+use crate::tileset::TileSet;
+
+/**
+ * A whole game, from an empty board, keeping the move order.
+ *
+ * The notation starts with a header holding the tiles of the
+ * first player, between brackets, followed by moves separated
+ * by whitespace. Each move is the square of the move, white moves
+ * are upper cased and black moves are lower cased:
+ *
+ * ```text
+ * [01234567] c2 B3 B1 C3 c1 D1
+ * ```
+ *
+ * See `Board::from_fen` for the tiles notation.
+ */
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct GameRecord {
+	tiles: TileSet,
+	moves: Vec<Move>,
+}
+
+/// The first move of a record that could not be played.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ReplayError {
+	/// Index of the move in the record.
+	pub index: usize,
+	/// The move and why it could not be played.
+	pub error: IllegalMove,
+	/// The position on which the move was played.
+	pub board: Board,
+}
+
+impl GameRecord {
+	/// A record without any move, using the tiles of the current
+	/// player of `board` as the first player's tiles. Records start
+	/// from an empty board, so there is none for a board with pieces
+	/// on it.
+	pub fn new(board: &Board) -> Option<GameRecord> {
+		if board.pieces_mask != 0 || board.side() != Side::First {
+			return None;
+		}
+		Some(GameRecord {
+			tiles: board.current_player.tiles(),
+			moves: Vec::new(),
+		})
+	}
+
+	pub fn parse(input: &str) -> Result<GameRecord, ParseError> {
+		let header_error = |offset, kind| ParseError::Tiles { offset, kind };
+
+		let mut chars = input.char_indices();
+		match chars.next() {
+			Some((_, '[')) => (),
+			Some((offset, chr)) => return Err(header_error(offset, InvalidCharacter(chr))),
+			None => return Err(header_error(0, UnexpectedEnd)),
+		}
+		let Some(end) = input.find(']') else {
+			return Err(header_error(input.len(), UnexpectedEnd));
+		};
+		let tiles = TileSet::from_fen_part(&input[1..end], 1)?;
+
+		let mut moves = Vec::new();
+		for token in input[end + 1..].split_whitespace() {
+			let offset = token.as_ptr() as usize - input.as_ptr() as usize;
+			moves.push(parse_move(token, offset)?);
+		}
+
+		Ok(GameRecord { tiles, moves })
+	}
+
+	pub fn moves(&self) -> &[Move] {
+		&self.moves
+	}
+
+	/// Append a move, it is only checked on replay.
+	pub fn push(&mut self, mov: Move) {
+		self.moves.push(mov);
+	}
+
+	pub fn initial_board(&self) -> Board {
+		Board::empty_with_tiles(self.tiles)
+	}
+
+	/// Every position of the game, starting with the empty board and
+	/// ending with the position after the last move.
+	pub fn replay(&self) -> Result<Vec<Board>, ReplayError> {
+		let mut boards = Vec::with_capacity(self.moves.len() + 1);
+		let mut board = self.initial_board();
+		boards.push(board);
+		for (index, mov) in self.moves.iter().enumerate() {
+			let previous = board;
+			board.play(*mov).map_err(|error| ReplayError {
+				index,
+				error,
+				board: previous,
+			})?;
+			boards.push(board);
+		}
+		Ok(boards)
+	}
+
+	/// The position after the last move.
+	pub fn final_board(&self) -> Result<Board, ReplayError> {
+		self.replay()
+			.map(|boards| *boards.last().expect("replay has at least one board"))
+	}
+}
+
+/// A move is written as its square, the case telling its color.
+/// `offset` is the position of `token` in the parsed record.
+fn parse_move(token: &str, offset: usize) -> Result<Move, ParseError> {
+	let error = |index, kind| ParseError::Move {
+		offset: offset + index,
+		kind,
+	};
+	let mut chars = token.char_indices();
+
+	let (x, color) = match chars.next() {
+		Some((_, chr @ 'a'..='e')) => (chr as u8 - b'a', Color::Black),
+		Some((_, chr @ 'A'..='E')) => (chr as u8 - b'A', Color::White),
+		Some((index, chr)) => return Err(error(index, InvalidCharacter(chr))),
+		None => return Err(error(0, UnexpectedEnd)),
+	};
+
+	let y = match chars.next() {
+		Some((_, chr @ '1'..='5')) => chr as u8 - b'1',
+		Some((index, chr)) => return Err(error(index, InvalidCharacter(chr))),
+		None => return Err(error(token.len(), UnexpectedEnd)),
+	};
+
+	if let Some((index, chr)) = chars.next() {
+		return Err(error(index, InvalidCharacter(chr)));
+	}
+
+	Ok(Move::new(x, y, color))
+}
+
+impl std::convert::TryFrom<&str> for GameRecord {
+	type Error = ParseError;
+	fn try_from(s: &str) -> Result<Self, Self::Error> {
+		GameRecord::parse(s)
+	}
+}
+
+impl std::fmt::Display for GameRecord {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "[")?;
+		for tile in self.tiles {
+			write!(f, "{:x}", tile)?;
+		}
+		write!(f, "]")?;
+		for mov in &self.moves {
+			let col = (b'a' + mov.x()) as char;
+			let col = match mov.color {
+				Color::Black => col,
+				Color::White => col.to_ascii_uppercase(),
+			};
+			write!(f, " {}{}", col, mov.y() + 1)?;
+		}
+		Ok(())
+	}
+}
+
+impl std::fmt::Display for ReplayError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} (move {}) on board {}",
+			self.error,
+			self.index + 1,
+			self.board
+		)
+	}
+}
+
+impl std::error::Error for ReplayError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(&self.error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::error::IllegalMoveKind;
+
+	#[test]
+	fn test_parse_and_format() {
+		let notation = "[01234567] c2 B3 B1 C3 c1 D1";
+		let record = GameRecord::parse(notation).unwrap();
+		assert_eq!(record.moves().len(), 6);
+		assert_eq!(record.moves()[0], Move::black(2, 1));
+		assert_eq!(record.moves()[1], Move::white(1, 2));
+		assert_eq!(record.to_string(), notation);
+		assert_eq!(
+			record.final_board().unwrap().fen(),
+			"1wbw/2b/1ww// 01234567"
+		);
+
+		let spaced = GameRecord::parse("[01234567]\tc2  B3\nB1 C3\r\nc1 D1\n").unwrap();
+		assert_eq!(spaced, record);
+	}
+
+	#[test]
+	fn test_new() {
+		let record = GameRecord::new(&Board::empty()).unwrap();
+		assert_eq!(record.to_string(), "[01234567]");
+		let board = Board::from_fen("1wbw/2b/1ww// 01234567").unwrap();
+		assert_eq!(GameRecord::new(&board), None);
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		for (input, error) in [
+			(
+				"",
+				ParseError::Tiles {
+					offset: 0,
+					kind: UnexpectedEnd,
+				},
+			),
+			(
+				"0123",
+				ParseError::Tiles {
+					offset: 0,
+					kind: InvalidCharacter('0'),
+				},
+			),
+			(
+				"[01234567 c2",
+				ParseError::Tiles {
+					offset: 12,
+					kind: UnexpectedEnd,
+				},
+			),
+			(
+				"[0123456] c2",
+				ParseError::Tiles {
+					offset: 8,
+					kind: TileCount(7),
+				},
+			),
+			(
+				"[01234567] c2 f3",
+				ParseError::Move {
+					offset: 14,
+					kind: InvalidCharacter('f'),
+				},
+			),
+			(
+				"[01234567] c2  B6",
+				ParseError::Move {
+					offset: 16,
+					kind: InvalidCharacter('6'),
+				},
+			),
+			(
+				"[01234567] c2\n\tB6",
+				ParseError::Move {
+					offset: 16,
+					kind: InvalidCharacter('6'),
+				},
+			),
+		] {
+			assert_eq!(
+				GameRecord::parse(input),
+				Err(error),
+				"for input {:?}",
+				input
+			);
+		}
+	}
+
+	#[test]
+	fn test_replay_reports_first_illegal_move() {
+		let record = GameRecord::parse("[01234567] c2 B3 C2 a1").unwrap();
+		let error = record.replay().unwrap_err();
+		assert_eq!(error.index, 2);
+		assert_eq!(
+			error.error,
+			IllegalMove {
+				mov: Move::white(2, 1),
+				kind: IllegalMoveKind::Occupied,
+			}
+		);
+		assert_eq!(error.board.fen(), "/2b/1w// 01234567");
+	}
+}
@@ -0,0 +1,408 @@
+use crate::board::Board;
+use crate::color::Color;
+use crate::mov::Move;
+use crate::player::Player;
+use crate::tileset::TileSet;
+
+/**
+ * A way to draw a position: the board with hints on empty squares,
+ * between the tile racks of both players.
+ *
+ * Empty squares hint at which colors may be played there. Text
+ * renderers show `·` (or `.`) when both colors may be played, `b` or
+ * `w` when only one may, and `x` (or `×`) when none may.
+ *
+ * ```
+ * use ascacou::{Ascii, Board, Renderer};
+ *
+ * let board = Board::from_fen("bw/w/// 01234567").unwrap();
+ * println!("{}", Ascii.board(&board));
+ * ```
+ */
+pub trait Renderer {
+	/// The board, the opponent's rack above and the current player's
+	/// rack below.
+	fn board(&self, board: &Board) -> String;
+
+	/// Tiles of a player, tiles already on the board are highlighted.
+	fn rack(&self, player: &Player, played_tiles: TileSet) -> String;
+}
+
+/// Names of the renderers, as accepted by `renderer`.
+pub const RENDERER_NAMES: [&str; 4] = ["ansi", "ascii", "unicode", "svg"];
+
+/// A renderer from its name, for callers choosing one at runtime.
+pub fn renderer(name: &str) -> Option<&'static dyn Renderer> {
+	match name {
+		"ansi" => Some(&Ansi),
+		"ascii" => Some(&Ascii),
+		"unicode" => Some(&Unicode),
+		"svg" => Some(&Svg),
+		_ => None,
+	}
+}
+
+/// Colored terminal output, using ANSI escape codes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ansi;
+
+/// Plain ASCII, `#` for black pieces and `O` for white ones. Tiles on
+/// the board are marked with a `*`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ascii;
+
+/// Plain text using Unicode glyphs, `●` for black pieces and `○` for
+/// white ones. Tiles on the board are marked with a `*`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unicode;
+
+/// A standalone SVG image. Legal squares are hinted with small dots
+/// of the colors that may be played.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Svg;
+
+/// What a text renderer prints, the layout being shared.
+struct TextStyle {
+	black: &'static str,
+	white: &'static str,
+	/// Empty squares where both colors, only black, only white or no
+	/// color may be played.
+	hints: [&'static str; 4],
+	row_start: &'static str,
+	row_end: &'static str,
+	/// Start of a rack tile, not on the board and on the board.
+	tile_start: [&'static str; 2],
+	tile_end: &'static str,
+	/// Prefix of the number of a tile on the board.
+	played_mark: &'static str,
+}
+
+const ANSI: TextStyle = TextStyle {
+	black: "\x1b[31m●",
+	white: "\x1b[30m●",
+	hints: ["\x1b[30m·", "\x1b[30mb", "\x1b[30mw", "\x1b[30mx"],
+	row_start: " \x1b[47m",
+	row_end: " \x1b[0m",
+	tile_start: ["\x1b[47m", "\x1b[44m"],
+	tile_end: " \x1b[0m",
+	played_mark: " ",
+};
+
+const ASCII: TextStyle = TextStyle {
+	black: "#",
+	white: "O",
+	hints: [".", "b", "w", "x"],
+	row_start: " ",
+	row_end: "",
+	tile_start: ["", ""],
+	tile_end: " ",
+	played_mark: "*",
+};
+
+const UNICODE: TextStyle = TextStyle {
+	black: "●",
+	white: "○",
+	hints: ["·", "b", "w", "×"],
+	..ASCII
+};
+
+impl TextStyle {
+	fn piece(&self, color: Color) -> &'static str {
+		match color {
+			Color::Black => self.black,
+			Color::White => self.white,
+		}
+	}
+
+	fn rack(&self, player: &Player, played_tiles: TileSet) -> String {
+		let mut str = String::with_capacity(209);
+		for tile in player.tiles() {
+			let mark = if played_tiles.has(tile) {
+				self.played_mark
+			} else {
+				" "
+			};
+			str.push_str(&format!(" {}{: >2}  ", mark, tile));
+		}
+		str.push('\n');
+		for y in 0..2 {
+			let mut first_tile = true;
+
+			for tile in player.tiles() {
+				if first_tile {
+					first_tile = false;
+				} else {
+					str.push(' ')
+				}
+				str.push_str(self.tile_start[played_tiles.has(tile) as usize]);
+				for color in TileSet::tile_colors(tile)[y] {
+					str.push(' ');
+					str.push_str(self.piece(color));
+				}
+				str.push_str(self.tile_end);
+			}
+			str.push('\n');
+		}
+		str
+	}
+
+	fn board(&self, board: &Board) -> String {
+		let mut str = String::new();
+		str.push_str(&board.fen());
+		str.push('\n');
+
+		str.push_str(&self.rack(board.opponent(), board.played_tiles));
+
+		let spacing = " ".repeat((46/* tiles len */ - 12/* board len */) / 2);
+
+		str.push('\n');
+		str.push_str(&spacing);
+		str.push_str("   a b c d e\n");
+		let (black_legal, white_legal) = board.legal_moves();
+		for y in 0..5 {
+			str.push_str(&spacing);
+			str.push_str(&(y + 1).to_string());
+			str.push_str(self.row_start);
+
+			for x in 0..5 {
+				let position = Move::mask_at(x, y);
+
+				str.push(' ');
+
+				match board.color_at(x, y) {
+					Some(color) => str.push_str(self.piece(color)),
+					None => str.push_str(
+						match (black_legal & position != 0, white_legal & position != 0) {
+							(true, true) => self.hints[0],
+							(true, false) => self.hints[1],
+							(false, true) => self.hints[2],
+							(false, false) => self.hints[3],
+						},
+					),
+				}
+			}
+			str.push_str(self.row_end);
+			str.push('\n');
+		}
+		str.push('\n');
+		str.push('\n');
+		str.push_str(&self.rack(&board.current_player, board.played_tiles));
+
+		str
+	}
+}
+
+impl Renderer for Ansi {
+	fn board(&self, board: &Board) -> String {
+		ANSI.board(board)
+	}
+
+	fn rack(&self, player: &Player, played_tiles: TileSet) -> String {
+		ANSI.rack(player, played_tiles)
+	}
+}
+
+impl Renderer for Ascii {
+	fn board(&self, board: &Board) -> String {
+		ASCII.board(board)
+	}
+
+	fn rack(&self, player: &Player, played_tiles: TileSet) -> String {
+		ASCII.rack(player, played_tiles)
+	}
+}
+
+impl Renderer for Unicode {
+	fn board(&self, board: &Board) -> String {
+		UNICODE.board(board)
+	}
+
+	fn rack(&self, player: &Player, played_tiles: TileSet) -> String {
+		UNICODE.rack(player, played_tiles)
+	}
+}
+
+/// Size of a board square, in pixels.
+const SQUARE: u32 = 40;
+/// Size of a rack tile square, in pixels.
+const TILE_SQUARE: u32 = 12;
+/// Space around and between parts of the image.
+const MARGIN: u32 = 10;
+
+const RACK_HEIGHT: u32 = 2 * TILE_SQUARE;
+const WIDTH: u32 = 8 * (2 * TILE_SQUARE + MARGIN) + MARGIN;
+const BOARD_TOP: u32 = 2 * MARGIN + RACK_HEIGHT;
+const BOARD_LEFT: u32 = (WIDTH - 5 * SQUARE) / 2;
+const HEIGHT: u32 = BOARD_TOP + 5 * SQUARE + 2 * MARGIN + RACK_HEIGHT;
+
+impl Svg {
+	fn fill(color: Color) -> &'static str {
+		match color {
+			Color::Black => "black",
+			Color::White => "white",
+		}
+	}
+
+	/// A rack whose top is at `top`, as SVG elements.
+	fn rack_at(player: &Player, played_tiles: TileSet, top: u32) -> String {
+		let mut str = String::new();
+		for (index, tile) in player.tiles().enumerate() {
+			let left = MARGIN + index as u32 * (2 * TILE_SQUARE + MARGIN);
+			let class = if played_tiles.has(tile) {
+				"tile played"
+			} else {
+				"tile"
+			};
+			str.push_str(&format!(
+				"<g class=\"{}\" data-tile=\"{}\"{}>",
+				class,
+				tile,
+				if played_tiles.has(tile) {
+					" opacity=\"0.4\""
+				} else {
+					""
+				}
+			));
+			for (y, row) in TileSet::tile_colors(tile).iter().enumerate() {
+				for (x, &color) in row.iter().enumerate() {
+					str.push_str(&format!(
+						"<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"{}\" stroke=\"gray\"/>",
+						left + x as u32 * TILE_SQUARE,
+						top + y as u32 * TILE_SQUARE,
+						Svg::fill(color),
+						s = TILE_SQUARE,
+					));
+				}
+			}
+			str.push_str("</g>");
+		}
+		str
+	}
+}
+
+impl Renderer for Svg {
+	fn board(&self, board: &Board) -> String {
+		let mut str = format!(
+			"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+			w = WIDTH,
+			h = HEIGHT,
+		);
+		str.push_str(&format!(
+			"<rect width=\"{}\" height=\"{}\" fill=\"lightgray\"/>",
+			WIDTH, HEIGHT
+		));
+		str.push_str(&Svg::rack_at(board.opponent(), board.played_tiles, MARGIN));
+
+		let (black_legal, white_legal) = board.legal_moves();
+		for y in 0..5 {
+			for x in 0..5 {
+				let left = BOARD_LEFT + x as u32 * SQUARE;
+				let top = BOARD_TOP + y as u32 * SQUARE;
+				let (cx, cy) = (left + SQUARE / 2, top + SQUARE / 2);
+				str.push_str(&format!(
+					"<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"burlywood\" stroke=\"saddlebrown\"/>",
+					left,
+					top,
+					s = SQUARE,
+				));
+				let position = Move::mask_at(x, y);
+				match board.color_at(x, y) {
+					Some(color) => str.push_str(&format!(
+						"<circle class=\"piece\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\"/>",
+						cx,
+						cy,
+						SQUARE * 2 / 5,
+						Svg::fill(color),
+					)),
+					None => {
+						for (legal, color, offset) in [
+							(black_legal, Color::Black, -6),
+							(white_legal, Color::White, 6),
+						] {
+							if legal & position != 0 {
+								str.push_str(&format!(
+									"<circle class=\"hint\" cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"{}\" stroke=\"black\"/>",
+									cx as i32 + offset,
+									cy,
+									Svg::fill(color),
+								));
+							}
+						}
+					}
+				}
+			}
+		}
+
+		str.push_str(&Svg::rack_at(
+			&board.current_player,
+			board.played_tiles,
+			BOARD_TOP + 5 * SQUARE + MARGIN,
+		));
+		str.push_str("</svg>");
+		str
+	}
+
+	fn rack(&self, player: &Player, played_tiles: TileSet) -> String {
+		format!(
+			"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">{}</svg>",
+			Svg::rack_at(player, played_tiles, MARGIN),
+			w = WIDTH,
+			h = RACK_HEIGHT + 2 * MARGIN,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_ascii() {
+		let board = Board::from_fen("bwbw/ww1w/// 01234567").unwrap();
+		let rendered = Ascii.board(&board);
+		assert!(rendered.is_ascii());
+		let lines: Vec<&str> = rendered.lines().collect();
+		assert_eq!(lines[0], "bwbw/ww1w/// 01234567");
+		assert_eq!(lines[6], "                 1  # O # O .");
+		// White on c2 would form tile 1 again.
+		assert_eq!(lines[7], "                 2  O O b O .");
+		// Tile 1 is on the board.
+		assert_eq!(
+			lines[13],
+			"   0   * 1     2     3     4     5     6     7  "
+		);
+		assert_eq!(lines[14], " O O   # O   O #   # #   O O   # O   O #   # # ");
+	}
+
+	#[test]
+	fn test_ansi() {
+		let board = Board::from_fen("bwbw/ww1w/// 01234567").unwrap();
+		let rendered = Ansi.board(&board);
+		assert_eq!(
+			rendered.lines().nth(7),
+			Some(
+				"                 2 \x1b[47m \x1b[30m● \x1b[30m● \x1b[30mb \x1b[30m● \x1b[30m· \x1b[0m"
+			)
+		);
+		assert!(rendered.contains("\x1b[44m \x1b[31m● \x1b[30m● \x1b[0m"));
+		assert!(!Unicode.board(&board).contains('\x1b'));
+		assert_eq!(Color::Black.to_string(), "black");
+		for name in RENDERER_NAMES {
+			assert!(renderer(name).is_some());
+		}
+		assert!(renderer("html").is_none());
+	}
+
+	#[test]
+	fn test_svg() {
+		let board = Board::from_fen("bwbw/ww1w/// 01234567").unwrap();
+		let svg = Svg.board(&board);
+		assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+		assert_eq!(svg.matches("class=\"piece\"").count(), 7);
+		assert_eq!(svg.matches("class=\"tile played\"").count(), 1);
+		assert_eq!(
+			svg.matches("class=\"hint\"").count(),
+			board.possible_moves().count()
+		);
+	}
+}
@@ -0,0 +1,295 @@
+// Serde support, enabled with the `serde` feature.
+//
+// Types with a text notation are serialized as such: a `Board` is its
+// FEN, a `Move` is written as `ba1` and a `GameRecord` uses its own
+// notation. Deserialization runs the same checks as `Board::validate`
+// and `GameRecord::replay`, so invalid input is rejected.
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::board::Board;
+use crate::color::Color;
+use crate::error::Violation;
+use crate::mov::Move;
+use crate::player::Side;
+use crate::record::GameRecord;
+use crate::tileset::TileSet;
+
+/**
+ * A board as plain data, for formats where a FEN is not convenient.
+ *
+ * `rows[y][x]` is the piece at (x, y), the first row being the first
+ * one of the FEN. `tiles` are the tiles of the current player.
+ *
+ * ```
+ * use ascacou::{Board, StructuredBoard};
+ *
+ * let board = Board::from_fen("1wbw/2b/1ww// 03478bcd").unwrap();
+ * let json = serde_json::to_string(&StructuredBoard::from(&board)).unwrap();
+ * // Boards deserialize from either a FEN or a structured board.
+ * let parsed: Board = serde_json::from_str(&json).unwrap();
+ * assert_eq!(parsed, board);
+ * ```
+ */
+#[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StructuredBoard {
+	pub rows: [[Option<Color>; 5]; 5],
+	pub tiles: TileSet,
+	pub side: Side,
+	pub ply: u8,
+}
+
+impl From<&Board> for StructuredBoard {
+	fn from(board: &Board) -> StructuredBoard {
+		let mut rows = [[None; 5]; 5];
+		for (y, row) in rows.iter_mut().enumerate() {
+			for (x, square) in row.iter_mut().enumerate() {
+				*square = board.color_at(x as u8, y as u8);
+			}
+		}
+		StructuredBoard {
+			rows,
+			tiles: board.current_player.tiles(),
+			side: board.side(),
+			ply: board.ply(),
+		}
+	}
+}
+
+impl TryFrom<StructuredBoard> for Board {
+	type Error = Vec<Violation>;
+
+	fn try_from(structured: StructuredBoard) -> Result<Board, Self::Error> {
+		let mut pieces_mask = 0;
+		let mut black_mask = 0;
+		for (y, row) in structured.rows.iter().enumerate() {
+			for (x, square) in row.iter().enumerate() {
+				let mask = Move::mask_at(x as u8, y as u8);
+				match square {
+					Some(Color::Black) => black_mask |= mask,
+					Some(Color::White) => (),
+					None => continue,
+				}
+				pieces_mask |= mask;
+			}
+		}
+		let board = Board::from_parts(
+			pieces_mask,
+			black_mask,
+			structured.tiles,
+			structured.side,
+			structured.ply,
+		);
+		board.validate()?;
+		Ok(board)
+	}
+}
+
+fn violations_error<E: de::Error>(violations: Vec<Violation>) -> E {
+	E::custom(
+		violations
+			.iter()
+			.map(|violation| violation.to_string())
+			.collect::<Vec<String>>()
+			.join(", "),
+	)
+}
+
+impl Serialize for Board {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.fen())
+	}
+}
+
+impl<'de> Deserialize<'de> for Board {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Board, D::Error> {
+		struct BoardVisitor;
+
+		impl<'de> Visitor<'de> for BoardVisitor {
+			type Value = Board;
+
+			fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				write!(f, "a FEN or a structured board")
+			}
+
+			fn visit_str<E: de::Error>(self, fen: &str) -> Result<Board, E> {
+				let board = Board::from_fen(fen).map_err(E::custom)?;
+				board.validate().map_err(violations_error)?;
+				Ok(board)
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Board, A::Error> {
+				let structured =
+					StructuredBoard::deserialize(de::value::MapAccessDeserializer::new(map))?;
+				Board::try_from(structured).map_err(violations_error)
+			}
+		}
+
+		deserializer.deserialize_any(BoardVisitor)
+	}
+}
+
+impl Serialize for Move {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&String::from(self))
+	}
+}
+
+impl<'de> Deserialize<'de> for Move {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Move, D::Error> {
+		let notation = String::deserialize(deserializer)?;
+		Move::try_from(notation.as_str()).map_err(de::Error::custom)
+	}
+}
+
+impl Serialize for Color {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(match self {
+			Color::Black => "black",
+			Color::White => "white",
+		})
+	}
+}
+
+impl<'de> Deserialize<'de> for Color {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+		match String::deserialize(deserializer)?.as_str() {
+			"black" => Ok(Color::Black),
+			"white" => Ok(Color::White),
+			other => Err(de::Error::unknown_variant(other, &["black", "white"])),
+		}
+	}
+}
+
+impl Serialize for Side {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(match self {
+			Side::First => "first",
+			Side::Second => "second",
+		})
+	}
+}
+
+impl<'de> Deserialize<'de> for Side {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Side, D::Error> {
+		match String::deserialize(deserializer)?.as_str() {
+			"first" => Ok(Side::First),
+			"second" => Ok(Side::Second),
+			other => Err(de::Error::unknown_variant(other, &["first", "second"])),
+		}
+	}
+}
+
+/// Tiles are a list of numbers, as they may not be a whole
+/// distribution.
+impl Serialize for TileSet {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut seq = serializer.serialize_seq(Some(self.len()))?;
+		for tile in *self {
+			seq.serialize_element(&tile)?;
+		}
+		seq.end()
+	}
+}
+
+impl<'de> Deserialize<'de> for TileSet {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<TileSet, D::Error> {
+		struct TileSetVisitor;
+
+		impl<'de> Visitor<'de> for TileSetVisitor {
+			type Value = TileSet;
+
+			fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				write!(f, "a list of different tiles between 0 and 15")
+			}
+
+			fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TileSet, A::Error> {
+				let mut tiles = TileSet::empty();
+				while let Some(tile) = seq.next_element::<u8>()? {
+					if tile >= 16 {
+						return Err(de::Error::custom(format!("there is no tile {}", tile)));
+					}
+					tiles = tiles
+						.try_add(tile)
+						.ok_or_else(|| de::Error::custom(format!("duplicate tile {}", tile)))?;
+				}
+				Ok(tiles)
+			}
+		}
+
+		deserializer.deserialize_seq(TileSetVisitor)
+	}
+}
+
+impl Serialize for GameRecord {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+impl<'de> Deserialize<'de> for GameRecord {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GameRecord, D::Error> {
+		let notation = String::deserialize(deserializer)?;
+		let record = GameRecord::parse(&notation).map_err(de::Error::custom)?;
+		record.replay().map_err(de::Error::custom)?;
+		Ok(record)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn round_trip<T>(value: T, json: &str)
+	where
+		T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+	{
+		assert_eq!(serde_json::to_string(&value).unwrap(), json);
+		assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+	}
+
+	#[test]
+	fn test_round_trips() {
+		let board = Board::from_fen("1wbw/2b/1ww// 03478bcd s 1").unwrap();
+		round_trip(board, "\"1wbw/2b/1ww// 03478bcd s 1\"");
+		round_trip(Move::black(0, 0), "\"ba1\"");
+		round_trip(Color::White, "\"white\"");
+		round_trip(
+			TileSet::try_from([0, 3, 4, 7, 8, 11, 12, 13]).unwrap(),
+			"[0,3,4,7,8,11,12,13]",
+		);
+		round_trip(
+			GameRecord::parse("[01234567] c2 B3").unwrap(),
+			"\"[01234567] c2 B3\"",
+		);
+
+		let structured = StructuredBoard::from(&board);
+		assert_eq!(structured.rows[0][1], Some(Color::White));
+		assert_eq!(structured.rows[1][2], Some(Color::Black));
+		let json = serde_json::to_string(&structured).unwrap();
+		assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+	}
+
+	#[test]
+	fn test_rejects_invalid_input() {
+		for json in [
+			// Not a FEN.
+			"\"1wbw/2b\"",
+			// Tile 0 completed twice.
+			"\"ww1ww/ww1ww//// 01234567\"",
+			// Side does not match ply.
+			"{\"rows\":[[null,null,null,null,null],[null,null,null,null,null],\
+			 [null,null,null,null,null],[null,null,null,null,null],\
+			 [null,null,null,null,null]],\"tiles\":[0,1,2,3,4,5,6,7],\
+			 \"side\":\"second\",\"ply\":0}",
+		] {
+			assert!(serde_json::from_str::<Board>(json).is_err(), "for {}", json);
+		}
+		assert!(serde_json::from_str::<Move>("\"ba6\"").is_err());
+		assert!(serde_json::from_str::<TileSet>("[1,1]").is_err());
+		assert!(serde_json::from_str::<TileSet>("[16]").is_err());
+		// Second move is not possible.
+		assert!(serde_json::from_str::<GameRecord>("\"[01234567] c2 C2\"").is_err());
+	}
+}
@@ -0,0 +1,200 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::board::Board;
+use crate::error::{IllegalPick, Violation};
+use crate::player::{Player, Side};
+use crate::tileset::TileSet;
+
+/// How the tiles were distributed between both players.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SetupMethod {
+	/// The first player was dealt a given set of tiles.
+	Dealt,
+	/// Tiles were shuffled with a seeded generator, replaying the
+	/// same seed gives the same distribution.
+	Random { seed: u64 },
+	/// Players picked tiles one at a time, the first player starting.
+	/// Tiles are listed in the order they were picked.
+	Draft { picks: Vec<u8> },
+}
+
+/**
+ * The setup phase of a game, before any move: both players end up
+ * with 8 tiles each, and the game may start on `Setup::board`.
+ *
+ * Dealt and random setups are complete as soon as they are created,
+ * a draft is complete once every tile was picked:
+ *
+ * ```
+ * use ascacou::{Setup, Side};
+ *
+ * let mut setup = Setup::draft();
+ * for tile in [0, 15, 1, 14, 2, 13, 3, 12, 4, 11, 5, 10, 6, 9, 7] {
+ * 	setup.pick(tile).unwrap();
+ * }
+ * // The last tile is given to the second player.
+ * assert!(setup.is_complete());
+ * assert_eq!(setup.to_pick(), None);
+ * assert_eq!(setup.board().unwrap().fen(), "//// 01234567");
+ * ```
+ */
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Setup {
+	method: SetupMethod,
+	/// Tiles of the first player so far.
+	first: TileSet,
+	/// Tiles of the second player so far.
+	second: TileSet,
+}
+
+impl Setup {
+	/// The first player owns `tiles`, the second one owns the others.
+	pub fn dealt(tiles: TileSet) -> Result<Setup, Violation> {
+		Player::new(tiles)?;
+		Ok(Setup {
+			method: SetupMethod::Dealt,
+			first: tiles,
+			second: !tiles,
+		})
+	}
+
+	/// A random distribution, which is the same for a given `seed`.
+	pub fn random(seed: u64) -> Setup {
+		let (first, second) = Player::random_set(&mut StdRng::seed_from_u64(seed));
+		Setup {
+			method: SetupMethod::Random { seed },
+			first: first.tiles(),
+			second: second.tiles(),
+		}
+	}
+
+	/// An alternating draft, starting with no tile dealt.
+	pub fn draft() -> Setup {
+		Setup {
+			method: SetupMethod::Draft { picks: Vec::new() },
+			first: TileSet::empty(),
+			second: TileSet::empty(),
+		}
+	}
+
+	pub fn method(&self) -> &SetupMethod {
+		&self.method
+	}
+
+	/// Tiles no player owns yet.
+	pub fn available(&self) -> TileSet {
+		!(self.first | self.second)
+	}
+
+	pub fn tiles(&self, side: Side) -> TileSet {
+		match side {
+			Side::First => self.first,
+			Side::Second => self.second,
+		}
+	}
+
+	/// The side expected to pick the next tile, if any.
+	pub fn to_pick(&self) -> Option<Side> {
+		match &self.method {
+			SetupMethod::Draft { picks } if picks.len() < 16 => {
+				Some(Side::from_ply(picks.len() as u8))
+			}
+			_ => None,
+		}
+	}
+
+	/// Give `tile` to the side expected to pick. Once a single tile
+	/// is left, it is given to the second player right away.
+	pub fn pick(&mut self, tile: u8) -> Result<Side, IllegalPick> {
+		let side = self.to_pick().ok_or(IllegalPick::Complete)?;
+		if tile >= 16 {
+			return Err(IllegalPick::OutOfRange(tile));
+		}
+		if !self.available().has(tile) {
+			return Err(IllegalPick::AlreadyPicked(tile));
+		}
+		self.give(tile, side);
+		if self.available().len() == 1 {
+			let last = self.available().next().expect("one tile is left");
+			self.give(last, Side::Second);
+		}
+		Ok(side)
+	}
+
+	fn give(&mut self, tile: u8, side: Side) {
+		let tiles = match side {
+			Side::First => &mut self.first,
+			Side::Second => &mut self.second,
+		};
+		*tiles = tiles
+			.try_add(tile)
+			.expect("tile is available, checked by caller");
+		if let SetupMethod::Draft { picks } = &mut self.method {
+			picks.push(tile);
+		}
+	}
+
+	pub fn is_complete(&self) -> bool {
+		self.available().is_empty()
+	}
+
+	/// The empty board starting the game, once every tile is owned.
+	pub fn board(&self) -> Option<Board> {
+		if !self.is_complete() {
+			return None;
+		}
+		Some(
+			Board::empty_with_distribution(self.first)
+				.expect("players own 8 tiles each once complete"),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_dealt() {
+		let tiles = TileSet::try_from([0, 3, 4, 7, 8, 11, 12, 13]).unwrap();
+		let setup = Setup::dealt(tiles).unwrap();
+		assert_eq!(setup.tiles(Side::Second), !tiles);
+		assert_eq!(setup.board().unwrap().fen(), "//// 03478bcd");
+		assert_eq!(
+			Setup::dealt(TileSet::try_from([0, 1]).unwrap()),
+			Err(Violation::TileDistribution)
+		);
+	}
+
+	#[test]
+	fn test_random_is_seeded() {
+		let setup = Setup::random(42);
+		assert_eq!(setup.method(), &SetupMethod::Random { seed: 42 });
+		assert_eq!(setup.board(), Setup::random(42).board());
+		assert_eq!(setup.board().unwrap().validate(), Ok(()));
+	}
+
+	#[test]
+	fn test_draft() {
+		let mut setup = Setup::draft();
+		assert_eq!(setup.board(), None);
+		assert_eq!(setup.pick(3), Ok(Side::First));
+		assert_eq!(setup.pick(3), Err(IllegalPick::AlreadyPicked(3)));
+		assert_eq!(setup.pick(16), Err(IllegalPick::OutOfRange(16)));
+		assert_eq!(setup.to_pick(), Some(Side::Second));
+		for tile in [0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14] {
+			setup.pick(tile).unwrap();
+		}
+		assert_eq!(setup.pick(15), Err(IllegalPick::Complete));
+		assert_eq!(
+			setup.tiles(Side::First),
+			TileSet::try_from([3, 1, 4, 6, 8, 10, 12, 14]).unwrap()
+		);
+		match setup.method() {
+			SetupMethod::Draft { picks } => assert_eq!(picks.len(), 16),
+			method => panic!("unexpected method {:?}", method),
+		}
+		assert_eq!(setup.board().unwrap().validate(), Ok(()));
+	}
+}
@@ -0,0 +1,196 @@
+use crate::color::Color;
+use crate::mov::Move;
+
+/**
+ * One of the 16 symmetries of an Ascacou position: one of the 8
+ * symmetries of the square, optionally followed by a color swap.
+ *
+ * Moving squares around also moves the pieces of each 2x2 tile,
+ * so tile numbers are remapped along with the board. Swapping
+ * colors maps tile `t` to `15 - t`.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct Symmetry {
+	geometry: Geometry,
+	swap_colors: bool,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+enum Geometry {
+	Identity,
+	/// Quarter turn, clockwise.
+	Rotate90,
+	Rotate180,
+	Rotate270,
+	/// Mirror along the vertical axis, columns are reversed.
+	MirrorColumns,
+	/// Mirror along the horizontal axis, rows are reversed.
+	MirrorRows,
+	/// Mirror along the a1-e5 diagonal.
+	Transpose,
+	/// Mirror along the e1-a5 diagonal.
+	AntiTranspose,
+}
+
+const GEOMETRIES: [Geometry; 8] = [
+	Geometry::Identity,
+	Geometry::Rotate90,
+	Geometry::Rotate180,
+	Geometry::Rotate270,
+	Geometry::MirrorColumns,
+	Geometry::MirrorRows,
+	Geometry::Transpose,
+	Geometry::AntiTranspose,
+];
+
+impl Geometry {
+	/// Map (x, y) in a square whose last index is `n`.
+	const fn map(self, x: u8, y: u8, n: u8) -> (u8, u8) {
+		match self {
+			Geometry::Identity => (x, y),
+			Geometry::Rotate90 => (n - y, x),
+			Geometry::Rotate180 => (n - x, n - y),
+			Geometry::Rotate270 => (y, n - x),
+			Geometry::MirrorColumns => (n - x, y),
+			Geometry::MirrorRows => (x, n - y),
+			Geometry::Transpose => (y, x),
+			Geometry::AntiTranspose => (n - y, n - x),
+		}
+	}
+
+	const fn inverse(self) -> Geometry {
+		match self {
+			Geometry::Rotate90 => Geometry::Rotate270,
+			Geometry::Rotate270 => Geometry::Rotate90,
+			other => other,
+		}
+	}
+}
+
+impl Symmetry {
+	pub const IDENTITY: Symmetry = Symmetry {
+		geometry: Geometry::Identity,
+		swap_colors: false,
+	};
+
+	/// Every symmetry, starting with the identity.
+	pub fn all() -> impl Iterator<Item = Symmetry> {
+		[false, true].into_iter().flat_map(|swap_colors| {
+			GEOMETRIES.into_iter().map(move |geometry| Symmetry {
+				geometry,
+				swap_colors,
+			})
+		})
+	}
+
+	pub const fn swaps_colors(&self) -> bool {
+		self.swap_colors
+	}
+
+	/// The symmetry that takes back this one.
+	pub const fn inverse(&self) -> Symmetry {
+		Symmetry {
+			geometry: self.geometry.inverse(),
+			swap_colors: self.swap_colors,
+		}
+	}
+
+	/// Map a square, given as (x, y) in the 0..5 range.
+	pub const fn apply_square(&self, x: u8, y: u8) -> (u8, u8) {
+		self.geometry.map(x, y, 4)
+	}
+
+	pub const fn apply_color(&self, color: Color) -> Color {
+		match (self.swap_colors, color) {
+			(false, color) => color,
+			(true, Color::Black) => Color::White,
+			(true, Color::White) => Color::Black,
+		}
+	}
+
+	pub const fn apply_move(&self, mov: Move) -> Move {
+		let (x, y) = self.apply_square(mov.x(), mov.y());
+		Move::new(x, y, self.apply_color(mov.color))
+	}
+
+	/// Map a move of the transformed position back to the original
+	/// position, e.g. a move found on a canonical board.
+	pub const fn revert_move(&self, mov: Move) -> Move {
+		self.inverse().apply_move(mov)
+	}
+
+	/// Map a tile number, see `Board::from_fen` for tile numbering.
+	pub const fn apply_tile(&self, tile: u8) -> u8 {
+		debug_assert!(tile < 16);
+		let mut mapped = 0;
+		let mut bit = 0;
+		while bit < 4 {
+			if tile & (1 << bit) != 0 {
+				let (x, y) = self.geometry.map(bit % 2, bit / 2, 1);
+				mapped |= 1 << (x + 2 * y);
+			}
+			bit += 1;
+		}
+		if self.swap_colors {
+			15 - mapped
+		} else {
+			mapped
+		}
+	}
+}
+
+impl std::fmt::Display for Symmetry {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let geometry = match self.geometry {
+			Geometry::Identity => "identity",
+			Geometry::Rotate90 => "rotate 90",
+			Geometry::Rotate180 => "rotate 180",
+			Geometry::Rotate270 => "rotate 270",
+			Geometry::MirrorColumns => "mirror columns",
+			Geometry::MirrorRows => "mirror rows",
+			Geometry::Transpose => "transpose",
+			Geometry::AntiTranspose => "anti-transpose",
+		};
+		if self.swap_colors {
+			write!(f, "{}, swap colors", geometry)
+		} else {
+			write!(f, "{}", geometry)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_apply_tile() {
+		let rotate90 = Symmetry {
+			geometry: Geometry::Rotate90,
+			swap_colors: false,
+		};
+		// Top-left black goes to top-right.
+		assert_eq!(rotate90.apply_tile(0b0001), 0b0010);
+		// Top row goes to right column.
+		assert_eq!(rotate90.apply_tile(0b0011), 0b1010);
+		let swap = Symmetry {
+			geometry: Geometry::Identity,
+			swap_colors: true,
+		};
+		assert_eq!(swap.apply_tile(0b0011), 0b1100);
+	}
+
+	#[test]
+	fn test_inverse() {
+		for symmetry in Symmetry::all() {
+			for tile in 0..16 {
+				assert_eq!(
+					symmetry.inverse().apply_tile(symmetry.apply_tile(tile)),
+					tile
+				);
+			}
+			let mov = Move::black(1, 3);
+			assert_eq!(symmetry.revert_move(symmetry.apply_move(mov)), mov);
+		}
+	}
+}
@@ -1,17 +1,94 @@
 use crate::color::Color;
+use crate::error::{ParseError, ParseErrorKind::*, TileError};
 
 const BLACK_COLOR_PRESENCE: [u8; 16] = [0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4];
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+/**
+ * A set of tiles, tile `n` being bit `n` (see `Board::from_fen` for
+ * tile numbering).
+ *
+ * A `TileSet` is its own iterator, yielding tiles in increasing order.
+ * Set algebra is available through methods and the `|`, `&`, `-` and
+ * `!` operators, `!` giving the complement among the 16 tiles.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Default)]
 pub struct TileSet(u16);
 
 impl TileSet {
 	pub const fn empty() -> Self {
-		TileSet::new(0)
+		TileSet::from_bits(0)
 	}
 
-	pub const fn new(values: u16) -> Self {
-		TileSet(values)
+	/// The set of tiles whose bits are on, tile `n` being bit `n`,
+	/// see `bits`.
+	pub const fn from_bits(bits: u16) -> Self {
+		TileSet(bits)
+	}
+
+	pub const fn full() -> Self {
+		TileSet::from_bits(0xFFFF)
+	}
+
+	/// Parse exactly 8 different tiles written as hexadecimal digits,
+	/// e.g. `03478bcd`.
+	pub fn from_hex(input: &str) -> Result<TileSet, ParseError> {
+		TileSet::from_fen_part(input, 0)
+	}
+
+	/// Parse exactly 8 different tiles written as hexadecimal digits,
+	/// as in the tiles part of a FEN. `offset` is the position of
+	/// `input` in the whole parsed string, used to report errors.
+	pub(crate) fn from_fen_part(input: &str, offset: usize) -> Result<TileSet, ParseError> {
+		let error = |index, kind| ParseError::Tiles {
+			offset: offset + index,
+			kind,
+		};
+		let mut tiles = TileSet::empty();
+		let mut count = 0;
+		for (index, chr) in input.char_indices() {
+			// No need for a guard there, digit may not be greater than 15.
+			let Some(digit) = chr.to_digit(16) else {
+				return Err(error(index, InvalidCharacter(chr)));
+			};
+			if count == 8 {
+				return Err(error(index, TileCount(count + 1)));
+			}
+			tiles = tiles
+				.try_add(digit as u8)
+				.ok_or(error(index, DuplicateTile(digit as u8)))?;
+			count += 1;
+		}
+
+		if count < 8 {
+			return Err(error(input.len(), TileCount(count)));
+		}
+
+		Ok(tiles)
+	}
+
+	/// The set of `tiles`, which must be different numbers in the
+	/// 0..16 range.
+	pub fn try_from_iter<I: IntoIterator<Item = u8>>(tiles: I) -> Result<TileSet, TileError> {
+		tiles.into_iter().try_fold(TileSet::empty(), |set, tile| {
+			if tile >= 16 {
+				return Err(TileError::OutOfRange(tile));
+			}
+			set.try_add(tile).ok_or(TileError::Duplicate(tile))
+		})
+	}
+
+	/// The union of `tiles`, for tiles known to be in the 0..16 range.
+	pub(crate) fn union_of<I: IntoIterator<Item = u8>>(tiles: I) -> TileSet {
+		let mut bits = 0u16;
+		for tile in tiles {
+			bits |= 1 << tile;
+		}
+		TileSet::from_bits(bits)
+	}
+
+	/// One bit per tile, tile `n` being bit `n`.
+	pub const fn bits(&self) -> u16 {
+		self.0
 	}
 
 	pub const fn has(&self, val: u8) -> bool {
@@ -19,20 +96,24 @@ impl TileSet {
 		self.0 & (1 << val) != 0
 	}
 
-	pub const fn try_add(&mut self, val: u8) -> Option<TileSet> {
-		debug_assert!(val < 16);
+	/// This set with `val` added, `None` when `val` is not a tile or
+	/// is already in the set.
+	pub const fn try_add(&self, val: u8) -> Option<TileSet> {
+		if val >= 16 {
+			return None;
+		}
 		let tile_mask = 1 << val;
 		// We could use `has` here, but this avoids one
 		// extra bit shift operation.
 		match self.0 & tile_mask {
-			0 => Some(TileSet::new(self.0 | tile_mask)),
+			0 => Some(TileSet::from_bits(self.0 | tile_mask)),
 			_ => None,
 		}
 	}
 
 	pub const fn try_union(&self, other: &TileSet) -> Option<TileSet> {
 		match self.0 & other.0 {
-			0 => Some(TileSet::new(self.0 | other.0)),
+			0 => Some(TileSet::from_bits(self.0 | other.0)),
 			_ => None,
 		}
 	}
@@ -41,6 +122,57 @@ impl TileSet {
 		self.0 == 0xFFFF
 	}
 
+	pub const fn is_empty(&self) -> bool {
+		self.0 == 0
+	}
+
+	pub const fn len(&self) -> usize {
+		self.0.count_ones() as usize
+	}
+
+	pub const fn union(&self, other: &TileSet) -> TileSet {
+		TileSet::from_bits(self.0 | other.0)
+	}
+
+	pub const fn intersection(&self, other: &TileSet) -> TileSet {
+		TileSet::from_bits(self.0 & other.0)
+	}
+
+	pub const fn difference(&self, other: &TileSet) -> TileSet {
+		TileSet::from_bits(self.0 & !other.0)
+	}
+
+	pub const fn complement(&self) -> TileSet {
+		TileSet::from_bits(!self.0)
+	}
+
+	pub const fn is_subset(&self, other: &TileSet) -> bool {
+		self.0 & !other.0 == 0
+	}
+
+	/// Whether this set can be dealt to a player, i.e. it holds
+	/// exactly half of the tiles.
+	pub const fn is_distribution(&self) -> bool {
+		self.len() == 8
+	}
+
+	/// Colors of the squares of `tile`, by row then column: top left,
+	/// top right, bottom left, bottom right.
+	pub const fn tile_colors(tile: u8) -> [[Color; 2]; 2] {
+		debug_assert!(tile < 16);
+		const fn color(tile: u8, bit: u8) -> Color {
+			if tile & (1 << bit) == 0 {
+				Color::White
+			} else {
+				Color::Black
+			}
+		}
+		[
+			[color(tile, 0), color(tile, 1)],
+			[color(tile, 2), color(tile, 3)],
+		]
+	}
+
 	pub const fn most_present_color(&self) -> Color {
 		if self.count_blacks() >= 16 {
 			Color::Black
@@ -49,7 +181,8 @@ impl TileSet {
 		}
 	}
 
-	const fn count_blacks(&self) -> u8 {
+	/// Number of black squares over all tiles of the set.
+	pub const fn count_blacks(&self) -> u8 {
 		let mut count = 0;
 		let mut values = self.0;
 
@@ -71,7 +204,41 @@ impl std::ops::Not for TileSet {
 		// has exactly 16 tiles, hence every bit
 		// matters. Otherwise this would overflow
 		// in unknown territory.
-		TileSet::new(!self.0)
+		TileSet::from_bits(!self.0)
+	}
+}
+
+impl std::ops::BitOr for TileSet {
+	type Output = TileSet;
+
+	fn bitor(self, other: TileSet) -> TileSet {
+		self.union(&other)
+	}
+}
+
+impl std::ops::BitAnd for TileSet {
+	type Output = TileSet;
+
+	fn bitand(self, other: TileSet) -> TileSet {
+		self.intersection(&other)
+	}
+}
+
+impl std::ops::Sub for TileSet {
+	type Output = TileSet;
+
+	fn sub(self, other: TileSet) -> TileSet {
+		self.difference(&other)
+	}
+}
+
+/// Tiles as hexadecimal digits, as in a FEN.
+impl std::fmt::Display for TileSet {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for tile in *self {
+			write!(f, "{:x}", tile)?;
+		}
+		Ok(())
 	}
 }
 
@@ -94,34 +261,19 @@ impl Iterator for TileSet {
 	}
 }
 
-// TODO(refacto): There should be a way to only have one From<Iterator>
-//   there
-// NOTE: this is a bit unsafe, we assume tiles have a value between 0..16.
-impl From<Vec<u8>> for TileSet {
-	fn from(tiles: Vec<u8>) -> TileSet {
-		tiles.into_iter().collect()
-	}
-}
+impl TryFrom<Vec<u8>> for TileSet {
+	type Error = TileError;
 
-impl From<[u8; 8]> for TileSet {
-	fn from(tiles: [u8; 8]) -> TileSet {
-		tiles.iter().map(|e| *e).collect()
+	fn try_from(tiles: Vec<u8>) -> Result<TileSet, TileError> {
+		TileSet::try_from_iter(tiles)
 	}
 }
 
-impl From<std::collections::LinkedList<u8>> for TileSet {
-	fn from(tiles: std::collections::LinkedList<u8>) -> TileSet {
-		tiles.into_iter().collect()
-	}
-}
+impl<const N: usize> TryFrom<[u8; N]> for TileSet {
+	type Error = TileError;
 
-impl std::iter::FromIterator<u8> for TileSet {
-	fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> TileSet {
-		let mut values = 0u16;
-		for tile in iter {
-			values |= 1 << tile;
-		}
-		TileSet::new(values)
+	fn try_from(tiles: [u8; N]) -> Result<TileSet, TileError> {
+		TileSet::try_from_iter(tiles)
 	}
 }
 
@@ -131,11 +283,11 @@ mod tests {
 
 	#[test]
 	fn test_tileset_iterator() {
-		for tiles in vec![
+		for tiles in [
 			vec![0, 3, 5, 7, 8, 9, 10, 15],
 			vec![1, 2, 4, 6, 8, 9, 10, 11],
 		] {
-			let tileset: TileSet = tiles.clone().into();
+			let tileset: TileSet = TileSet::try_from(tiles.clone()).unwrap();
 			let collected_tiles: Vec<u8> = tileset.into_iter().collect();
 			assert_eq!(tiles, collected_tiles);
 		}
@@ -143,14 +295,14 @@ mod tests {
 
 	#[test]
 	fn test_tileset_has() {
-		let tileset: TileSet = vec![0, 3, 5, 7, 8, 9, 10, 15].into();
+		let tileset: TileSet = TileSet::try_from(vec![0, 3, 5, 7, 8, 9, 10, 15]).unwrap();
 		assert!(tileset.has(0));
 		assert!(tileset.has(3));
 		assert!(!tileset.has(4));
 		assert!(tileset.has(9));
 		assert!(!tileset.has(14));
 		assert!(tileset.has(15));
-		let tileset: TileSet = vec![1, 2, 4, 6, 8, 9, 10, 11].into();
+		let tileset: TileSet = TileSet::try_from(vec![1, 2, 4, 6, 8, 9, 10, 11]).unwrap();
 		assert!(!tileset.has(0));
 		assert!(!tileset.has(15));
 	}
@@ -158,18 +310,77 @@ mod tests {
 	#[test]
 	#[should_panic(expected = "val < 16")]
 	fn test_tileset_has_invalid_value() {
-		let tileset: TileSet = vec![0, 3, 5, 7, 8, 9, 10, 15].into();
+		let tileset: TileSet = TileSet::try_from(vec![0, 3, 5, 7, 8, 9, 10, 15]).unwrap();
 		tileset.has(16);
 	}
 
+	#[test]
+	fn test_try_from() {
+		assert_eq!(TileSet::try_from([1, 0]), Ok(TileSet::from_bits(0b11)));
+		assert_eq!(
+			TileSet::try_from(vec![0, 16]),
+			Err(TileError::OutOfRange(16))
+		);
+		assert_eq!(
+			TileSet::try_from_iter([3, 4, 3]),
+			Err(TileError::Duplicate(3))
+		);
+	}
+
+	#[test]
+	fn test_try_add() {
+		let tiles = TileSet::from_bits(0b101);
+		assert_eq!(tiles.try_add(1), Some(TileSet::from_bits(0b111)));
+		assert_eq!(tiles.try_add(2), None);
+		assert_eq!(tiles.try_add(16), None);
+		assert_eq!(tiles, TileSet::from_bits(0b101));
+	}
+
 	#[test]
 	fn test_count_blacks() {
-		let mostly_white: TileSet = vec![0, 1, 2, 3, 4, 5, 6, 7].into();
-		let mostly_black: TileSet = vec![8, 9, 10, 11, 12, 13, 14, 15].into();
-		let same_same: TileSet = vec![4, 5, 6, 7, 8, 9, 10, 11].into();
+		let mostly_white: TileSet = TileSet::try_from(vec![0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+		let mostly_black: TileSet = TileSet::try_from(vec![8, 9, 10, 11, 12, 13, 14, 15]).unwrap();
+		let same_same: TileSet = TileSet::try_from(vec![4, 5, 6, 7, 8, 9, 10, 11]).unwrap();
 
 		assert_eq!(mostly_white.count_blacks(), 12);
 		assert_eq!(mostly_black.count_blacks(), 20);
 		assert_eq!(same_same.count_blacks(), 16);
 	}
+
+	#[test]
+	fn test_set_algebra() {
+		let low: TileSet = TileSet::try_from(vec![0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+		let even: TileSet = TileSet::try_from(vec![0, 2, 4, 6, 8, 10, 12, 14]).unwrap();
+		assert_eq!((low | even).len(), 12);
+		assert_eq!(low & even, TileSet::try_from([0, 2, 4, 6]).unwrap());
+		assert_eq!((low - even).collect::<Vec<u8>>(), vec![1, 3, 5, 7]);
+		assert_eq!(!low | low, TileSet::full());
+		assert!((low & even).is_subset(&low));
+		assert!(!low.is_subset(&even));
+		assert!(low.is_distribution());
+		assert!(!(low | even).is_distribution());
+		assert!((low & !low).is_empty());
+	}
+
+	#[test]
+	fn test_from_hex_and_display() {
+		let tiles = TileSet::from_hex("03478bcd").unwrap();
+		assert_eq!(tiles.to_string(), "03478bcd");
+		assert_eq!(
+			TileSet::from_hex("0347"),
+			Err(ParseError::Tiles {
+				offset: 4,
+				kind: TileCount(4)
+			})
+		);
+	}
+
+	#[test]
+	fn test_tile_colors() {
+		use Color::*;
+		assert_eq!(
+			TileSet::tile_colors(0b0110),
+			[[White, Black], [Black, White]]
+		);
+	}
 }
@@ -0,0 +1,37 @@
+use crate::tileset::TileSet;
+
+/**
+ * A 2x2 window of the board, where tiles are formed. There are 16
+ * windows, see `Board::windows`.
+ *
+ * A window is complete once its 4 squares are filled, its tile is
+ * then on the board. Until then, the window may form any tile that
+ * fits the pieces already in it, and that is not on the board yet.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Window {
+	/// Column of the top left square, from 0 to 3.
+	pub x: u8,
+	/// Row of the top left square, from 0 to 3.
+	pub y: u8,
+	/// Number of filled squares, from 0 to 4.
+	pub filled: u8,
+	/// The tile formed, once every square is filled.
+	pub tile: Option<u8>,
+	/// Tiles that may still be formed in this window, empty once
+	/// complete.
+	pub achievable: TileSet,
+}
+
+impl Window {
+	/// Squares of the window, in the order of tile bits: top left,
+	/// top right, bottom left and bottom right.
+	pub const fn squares(&self) -> [(u8, u8); 4] {
+		let (x, y) = (self.x, self.y);
+		[(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)]
+	}
+
+	pub const fn is_complete(&self) -> bool {
+		self.filled == 4
+	}
+}
@@ -0,0 +1,77 @@
+// Zobrist keys, see https://www.chessprogramming.org/Zobrist_Hashing
+//
+// A board hash is the xor of one key per filled square and color,
+// and of one key per tile owned by the current player.
+
+use crate::color::Color;
+use crate::tileset::TileSet;
+
+/// SplitMix64, used to fill key tables at compile time.
+const fn split_mix(state: u64) -> (u64, u64) {
+	let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+	let mut z = state;
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	(state, z ^ (z >> 31))
+}
+
+const fn keys<const N: usize>(seed: u64) -> [u64; N] {
+	let mut keys = [0; N];
+	let mut state = seed;
+	let mut i = 0;
+	while i < N {
+		let (next_state, key) = split_mix(state);
+		state = next_state;
+		keys[i] = key;
+		i += 1;
+	}
+	keys
+}
+
+/// Indexed by the bit position of a square in 7x7 masks. Only
+/// positions inside the 5x5 area are ever used.
+const BLACK_KEYS: [u64; 64] = keys(0xA5CA_C0B1);
+const WHITE_KEYS: [u64; 64] = keys(0xA5CA_C0E2);
+const TILE_KEYS: [u64; 16] = keys(0xA5CA_C071);
+
+/// Xor of every tile key. Since the opponent owns every tile the
+/// current player does not, swapping players is a xor with this.
+pub(crate) const SWAP_PLAYERS_KEY: u64 = {
+	let mut key = 0;
+	let mut i = 0;
+	while i < 16 {
+		key ^= TILE_KEYS[i];
+		i += 1;
+	}
+	key
+};
+
+/// Key of a single square mask (one bit set).
+pub(crate) const fn square_key(mask: u64, color: Color) -> u64 {
+	let i = mask.trailing_zeros() as usize;
+	match color {
+		Color::Black => BLACK_KEYS[i],
+		Color::White => WHITE_KEYS[i],
+	}
+}
+
+pub(crate) fn tiles_key(tiles: TileSet) -> u64 {
+	tiles.fold(0, |key, tile| key ^ TILE_KEYS[tile as usize])
+}
+
+/// Hash of a whole position, computed from scratch.
+pub(crate) fn hash(pieces_mask: u64, black_mask: u64, current_tiles: TileSet) -> u64 {
+	let mut key = tiles_key(current_tiles);
+	let mut pieces = pieces_mask;
+	while pieces != 0 {
+		let mask = pieces & pieces.wrapping_neg();
+		pieces ^= mask;
+		let color = if black_mask & mask == 0 {
+			Color::White
+		} else {
+			Color::Black
+		};
+		key ^= square_key(mask, color);
+	}
+	key
+}
@@ -1,14 +1,30 @@
 use ascacou::Board;
 use clap::Parser;
-use std::sync::mpsc;
-use std::thread;
-use std::time::Instant;
+use std::io;
+use std::process;
+use std::time::{Duration, Instant};
+
+/// Parse a FEN and check that it describes a possible position.
+fn parse_board(fen: &str) -> Result<Board, String> {
+	let board = Board::from_fen(fen).map_err(|error| error.to_string())?;
+	board.validate().map_err(|violations| {
+		format!(
+			"invalid board: {}",
+			violations
+				.iter()
+				.map(|violation| violation.to_string())
+				.collect::<Vec<String>>()
+				.join(", ")
+		)
+	})?;
+	Ok(board)
+}
 
 #[derive(Parser, Debug)]
 #[clap(about, author, version)]
 struct Args {
 	/// Board in FEN format
-	#[clap(value_parser = Board::from_fen, default_value_t = Board::empty())]
+	#[clap(value_parser = parse_board, default_value_t = Board::empty())]
 	board: Board,
 
 	/// Maximum search depth (5-25)
@@ -18,49 +34,31 @@ struct Args {
 	/// Strict timeout in milliseconds
 	#[clap(short, long, default_value_t = 500, value_parser = clap::value_parser!(u64).range(1..))]
 	timeout: u64,
-}
 
-fn run_solver(board: &Board, depth: u8) -> Option<(i16, ascacou::Move, u128)> {
-	match minicou::solve(&board, Some(depth)) {
-		(score, Some(mov), explored_positions) => Some((score, mov, explored_positions)),
-		_ => None,
-	}
+	/// Talk the UCI-style engine protocol on standard input and
+	/// output, see `ascacou::Engine`. Other arguments are ignored.
+	#[clap(long)]
+	uci: bool,
 }
 
 fn main() {
 	let args = Args::parse();
-	let t0 = Instant::now();
-	let (tx, rx) = mpsc::channel();
-	let max_depth = args.depth;
-	let board = args.board;
-	thread::spawn(move || {
-		let mut depth = 4;
-		while depth < max_depth {
-			tx.send(
-				run_solver(&board, depth).map(|(eval, mov, explored)| (eval, mov, explored, depth)),
-			)
-			.unwrap();
-			depth += 1;
-		}
-	});
-
-	let mut best_result = None;
-	let timeout = std::time::Duration::from_millis(args.timeout);
-	let mut time_left = timeout.saturating_sub(Instant::now() - t0);
-	while !time_left.is_zero() {
-		if let Ok(result) = rx.recv_timeout(time_left) {
-			best_result = result;
+	if args.uci {
+		let input = io::BufReader::new(io::stdin());
+		if let Err(error) = ascacou::serve(&mut minicou::Minicou::default(), input, io::stdout()) {
+			eprintln!("{}", error);
+			process::exit(1);
 		}
-		time_left = timeout.saturating_sub(Instant::now() - t0);
+		return;
 	}
-	let (score, mov, explored_positions, depth) = best_result.expect(&format!(
-		"Could not find a solution. Called with:\n{:#?}",
-		args
-	));
+	let t0 = Instant::now();
+	let solution =
+		minicou::solve_within(&args.board, args.depth, Duration::from_millis(args.timeout))
+			.unwrap_or_else(|| panic!("Could not find a solution. Called with:\n{:#?}", args));
 
-	println!("Move: {}", mov);
+	println!("Move: {}", solution.mov);
 	println!("Time: {:.2?}", Instant::now() - t0);
-	println!("Score: {}", score);
-	println!("Depth: {}/{}", depth, max_depth);
-	println!("Explored positions: {}", explored_positions);
+	println!("Score: {}", solution.score);
+	println!("Depth: {}/{}", solution.depth, args.depth);
+	println!("Explored positions: {}", solution.explored_positions);
 }
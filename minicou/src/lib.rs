@@ -1,6 +1,5 @@
-#![feature(assert_matches)]
-#![feature(gen_blocks)]
-
+mod protocol;
 mod solver;
 
-pub use solver::{Solver, partial_solve, solve};
+pub use protocol::Minicou;
+pub use solver::{Solver, TimedSolution, partial_solve, solve, solve_until, solve_within};
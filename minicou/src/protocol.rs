@@ -0,0 +1,134 @@
+use crate::solver::solve_until;
+use ascacou::{Board, EngineOption, Info, Move, Score, Search};
+
+/// The minimax solver, searching with increasing depth until a
+/// limit of `go` is reached.
+pub struct Minicou {
+	/// Maximum depth when `go` is given no limit.
+	pub depth: u8,
+}
+
+impl Default for Minicou {
+	fn default() -> Minicou {
+		Minicou { depth: 8 }
+	}
+}
+
+impl ascacou::Engine for Minicou {
+	fn name(&self) -> String {
+		format!("minicou {}", env!("CARGO_PKG_VERSION"))
+	}
+
+	fn options(&self) -> Vec<EngineOption> {
+		vec![EngineOption {
+			name: "Depth",
+			default: 8,
+			min: 1,
+			max: 25,
+		}]
+	}
+
+	fn set_option(&mut self, name: &str, value: i64) {
+		if name == "Depth" {
+			self.depth = value as u8;
+		}
+	}
+
+	fn search(&mut self, board: &Board, search: &mut Search) -> Option<Move> {
+		let max_depth = match search.limits.depth {
+			Some(depth) => depth,
+			None if search.limits.is_empty() => self.depth,
+			None => u8::MAX,
+		};
+
+		let mut best = None;
+		let mut nodes = 0;
+		for depth in 1..=max_depth.max(1) {
+			// The first depth is always searched, to have a move.
+			if depth > 1 && search.should_stop(nodes) {
+				break;
+			}
+			let should_stop =
+				|explored: u128| depth > 1 && search.should_stop(nodes + explored as u64);
+			let Some(solution) = solve_until(board, depth, &should_stop) else {
+				break;
+			};
+			// The game was already searched to its end.
+			if solution.depth < depth {
+				break;
+			}
+			nodes += solution.explored_positions as u64;
+			best = Some(solution.mov);
+			search.report(Info {
+				depth: solution.depth,
+				score: Some(Score::Tiles(solution.score)),
+				nodes,
+				time: search.elapsed(),
+				pv: solution.pv,
+			});
+		}
+		best
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ascacou::{Engine, Limits};
+
+	fn search(board: &Board, limits: Limits, stopped: bool) -> (Option<Move>, Vec<Info>) {
+		let mut infos = vec![];
+		let mut report = |info| infos.push(info);
+		let mov =
+			Minicou::default().search(board, &mut Search::new(limits, &|| stopped, &mut report));
+		(mov, infos)
+	}
+
+	#[test]
+	fn test_search() {
+		let board = Board::from_fen("2bbw/bww1w/w1w1w/1w1bw/wbb1b 013679ce").unwrap();
+		let limits = Limits {
+			depth: Some(4),
+			..Limits::default()
+		};
+		let (mov, infos) = search(&board, limits, false);
+		assert_eq!(infos.len(), 4);
+		assert!(infos.is_sorted_by_key(|info| info.nodes));
+		let last = infos.last().unwrap();
+		assert_eq!(mov, Some(last.pv[0]));
+		assert!(last.pv.len() <= 4);
+		assert_eq!(
+			(last.score, mov),
+			(
+				Some(Score::Tiles(
+					solve_until(&board, 4, &|_| false).unwrap().score
+				)),
+				Some(solve_until(&board, 4, &|_| false).unwrap().mov)
+			)
+		);
+
+		// Stopped searches still have a move.
+		let (mov, infos) = search(&board, Limits::default(), true);
+		assert!(mov.is_some());
+		assert_eq!(infos.len(), 1);
+
+		// Endgames are searched to the end without going deeper.
+		let mut board = board;
+		while board.possible_moves().count() > 4 {
+			let mov = board.possible_moves().next().unwrap();
+			board.play(mov).unwrap();
+		}
+		let (mov, infos) = search(&board, Limits::default(), false);
+		assert!(mov.is_some());
+		assert!(infos.len() < 8);
+	}
+
+	#[test]
+	fn test_set_option() {
+		let mut minicou = Minicou::default();
+		minicou.set_option("Depth", 3);
+		assert_eq!(minicou.depth, 3);
+		minicou.set_option("MoveTime", 100);
+		assert_eq!(minicou.depth, 3);
+	}
+}
@@ -1,8 +1,18 @@
-use ascacou::{Board, Color::*, Move};
+use ascacou::{Board, Color::*, Move, Undo};
+use std::time::{Duration, Instant};
 
-pub struct Solver {
+pub struct Solver<'a> {
 	explored_positions: u128,
-	transposition_table: std::collections::HashMap<u128, EvaluationScore>,
+	transposition_table: std::collections::HashMap<u64, EvaluationScore>,
+	/// Checked every `ABORT_CHECK_INTERVAL` positions with the number
+	/// of explored positions, the search is aborted once it is true.
+	should_stop: Option<&'a dyn Fn(u128) -> bool>,
+	aborted: bool,
+	/// Triangular table of principal variations: `pv[depth]` is the
+	/// best line found from the last position searched with `depth`
+	/// moves left. Depth decreases by one at each move, so a line is
+	/// its best move followed by the line one depth below.
+	pv: Vec<Vec<Move>>,
 }
 
 pub use std::primitive::i16 as EvaluationScore;
@@ -31,6 +41,8 @@ const MAX_SCORE: EvaluationScore = 100;
 /// |            7 |  -1 |    -3 |     4 |     0 |
 const FORCED_MOVE_DEPTH: u8 = 3;
 
+const ABORT_CHECK_INTERVAL: u128 = 1024;
+
 macro_rules! heuristic_moves {
 	( $first_color:ident => $last_color:ident [ $( ($x:expr, $y:expr) )* ] ) => {
 		[
@@ -68,69 +80,100 @@ const HEURISTIC_WHITE_FIRST: [Move; 50] = heuristic_moves!(white => black [
 	(0, 0) (0, 4) (4, 0) (4, 4)
 ]);
 
-impl Solver {
-	fn new() -> Solver {
+impl<'a> Solver<'a> {
+	fn new(should_stop: Option<&'a dyn Fn(u128) -> bool>) -> Solver<'a> {
 		Solver {
 			explored_positions: 0,
 			transposition_table: std::collections::HashMap::new(),
+			should_stop,
+			aborted: false,
+			pv: Vec::new(),
 		}
 	}
 
-	fn negamax0<'a>(
+	/// Set the line at `depth` to `mov` followed by the line one
+	/// depth below, just searched from the position after `mov`.
+	fn update_pv(&mut self, depth: u8, mov: Move) {
+		let (lower, upper) = self.pv.split_at_mut(depth as usize);
+		let line = &mut upper[0];
+		line.clear();
+		line.push(mov);
+		line.extend_from_slice(&lower[depth as usize - 1]);
+	}
+
+	fn negamax0(
 		&mut self,
-		board: &'a Board,
+		board: &mut Board,
 		mut alpha: EvaluationScore,
 		beta: EvaluationScore,
 		depth: u8,
 	) -> (EvaluationScore, Option<Move>) {
 		self.explored_positions += 1;
+		self.pv.resize(depth as usize + 1, Vec::new());
+		self.pv[depth as usize].clear();
 
 		if depth == 0 {
 			return (evaluation(board), None);
 		}
 
-		let boards_and_moves = next_boards::<(Board, Move)>(&board, false);
+		let mut moves = next_moves(board, false);
 
 		let mut best_mov: Option<Move> = None;
 		let mut terminal = true;
-		for (board, mov) in boards_and_moves {
+		while let Some((mov, undo)) = moves.play_next(board) {
 			terminal = false;
-			let score = -self.negamax(&board, -beta, -alpha, depth - 1);
+			let score = -self.negamax(board, -beta, -alpha, depth - 1);
+			board.undo(undo);
 			if score >= beta {
+				self.update_pv(depth, mov);
 				return (score, Some(mov));
 			}
 
 			if score > alpha {
 				alpha = score;
 				best_mov = Some(mov);
+				self.update_pv(depth, mov);
 			}
 		}
 		if terminal {
 			alpha = evaluation(board);
 		}
 
-		return (alpha, best_mov);
+		(alpha, best_mov)
 	}
 
 	fn negamax(
 		&mut self,
-		board: &Board,
+		board: &mut Board,
 		mut alpha: EvaluationScore,
 		mut beta: EvaluationScore,
 		depth: u8,
 	) -> EvaluationScore {
 		debug_assert!(alpha < beta);
 		self.explored_positions += 1;
+		self.pv[depth as usize].clear();
+
+		if self.explored_positions.is_multiple_of(ABORT_CHECK_INTERVAL)
+			&& let Some(should_stop) = self.should_stop
+		{
+			self.aborted |= should_stop(self.explored_positions);
+		}
+		// The score does not matter, the whole search is dropped.
+		if self.aborted {
+			return alpha;
+		}
 
-		let key = key(&board);
+		// TODO(perf): try partial key matching.
+		// See https://www.chessprogramming.org/Transposition_Table
+		let key = board.hash();
 
 		// Reduce window by finding a transposition with a lower beta.
-		if let Some(cached_beta) = self.transposition_table.get(&key) {
-			if beta > *cached_beta {
-				beta = *cached_beta;
-				if alpha >= beta {
-					return beta;
-				}
+		if let Some(cached_beta) = self.transposition_table.get(&key)
+			&& beta > *cached_beta
+		{
+			beta = *cached_beta;
+			if alpha >= beta {
+				return beta;
 			}
 		}
 
@@ -138,24 +181,16 @@ impl Solver {
 			return evaluation(board);
 		}
 
-		let boards = next_boards::<Board>(&board, depth <= FORCED_MOVE_DEPTH);
+		let mut moves = next_moves(board, depth <= FORCED_MOVE_DEPTH);
 
 		let mut terminal = true;
 
-		for board in boards {
+		// Moves are played and taken back on the same board rather
+		// than copying a new board for each node.
+		while let Some((mov, undo)) = moves.play_next(board) {
 			terminal = false;
-			// TODO(perf): we could have the board being part of the solver as mutable, and
-			//  have a function to make a move and unmake a move. This way we would not
-			//  instanciate any new board, may be much more performant.
-			//
-			//  eg:
-			//  self.board.make_move(move)
-			//  let score = ...
-			//  self.board.rewind_move(move)
-			//
-			//  a simple implementation of this idea only yields a quite small improvement (from 1.9ms to 1.7ms for a
-			//  full random game simulation)
-			let score = -self.negamax(&board, -beta, -alpha, depth - 1);
+			let score = -self.negamax(board, -beta, -alpha, depth - 1);
+			board.undo(undo);
 
 			if score >= beta {
 				return score;
@@ -163,15 +198,16 @@ impl Solver {
 
 			if score > alpha {
 				alpha = score;
+				self.update_pv(depth, mov);
 			}
 		}
 
 		if terminal {
-			alpha = evaluation(&board);
+			alpha = evaluation(board);
 		}
 
 		self.transposition_table.insert(key, alpha);
-		return alpha;
+		alpha
 	}
 }
 
@@ -191,33 +227,23 @@ impl Iterator for MaskIterator {
 	}
 }
 
-fn next_boards<'a, T>(board: &'a Board, forced: bool) -> MoveIterator<'a, T> {
+fn next_moves(board: &Board, forced: bool) -> MoveIterator {
 	if forced {
 		let x = board.pieces_mask;
-		MoveIterator::Forced(ForcedMoveIterator {
-			almost_full_mask: MaskIterator(
-				(!x & (x >> 1) & (x >> 7) & (x >> 8))
-					| (!x & (x << 1) & (x >> 6) & (x >> 7))
-					| (!x & (x >> 1) & (x << 6) & (x << 7))
-					| (!x & (x << 1) & (x << 7) & (x << 8)),
-			),
-			board,
-			return_type: std::marker::PhantomData,
-		})
+		MoveIterator::Forced(MaskIterator(
+			(!x & (x >> 1) & (x >> 7) & (x >> 8))
+				| (!x & (x << 1) & (x >> 6) & (x >> 7))
+				| (!x & (x >> 1) & (x << 6) & (x << 7))
+				| (!x & (x << 1) & (x << 7) & (x << 8)),
+		))
 	} else {
-		let black_fav = board.current_player.favorite_color == ascacou::Color::Black;
+		let black_fav = board.current_player.favorite_color() == ascacou::Color::Black;
 		let heuristic = if black_fav {
 			&HEURISTIC_BLACK_FIRST
 		} else {
 			&HEURISTIC_WHITE_FIRST
 		};
-		MoveIterator::All(AllMoveIterator {
-			board,
-			index: 0,
-			len: heuristic.len(),
-			heuristic,
-			return_type: std::marker::PhantomData,
-		})
+		MoveIterator::All(heuristic.iter())
 	}
 }
 
@@ -225,119 +251,48 @@ fn next_boards<'a, T>(board: &'a Board, forced: bool) -> MoveIterator<'a, T> {
 // and forced moves where actually
 // similar to AllMoves, we ended up
 // with better performance.
-enum MoveIterator<'a, T> {
-	Forced(ForcedMoveIterator<'a, T>),
-	All(AllMoveIterator<'a, T>),
-}
-
-impl<'a> Iterator for MoveIterator<'a, Board> {
-	type Item = Board;
-	fn next(&mut self) -> Option<Board> {
-		match self {
-			MoveIterator::Forced(it) => it.next(),
-			MoveIterator::All(it) => it.next(),
-		}
-	}
+//
+// This is not an `Iterator` since it does not borrow the board:
+// each move is played in place, and must be taken back before
+// asking for the next one.
+enum MoveIterator {
+	/// Squares where only one color may be played to fill a tile.
+	Forced(MaskIterator),
+	All(std::slice::Iter<'static, Move>),
 }
 
-impl<'a> Iterator for MoveIterator<'a, (Board, Move)> {
-	type Item = (Board, Move);
-	fn next(&mut self) -> Option<(Board, Move)> {
+impl MoveIterator {
+	fn play_next(&mut self, board: &mut Board) -> Option<(Move, Undo)> {
 		match self {
-			MoveIterator::Forced(it) => it.next(),
-			MoveIterator::All(it) => it.next(),
-		}
-	}
-}
-
-struct ForcedMoveIterator<'a, T> {
-	almost_full_mask: MaskIterator,
-	board: &'a Board,
-	return_type: std::marker::PhantomData<T>,
-}
-
-impl<'a> Iterator for ForcedMoveIterator<'a, Board> {
-	type Item = Board;
-
-	fn next(&mut self) -> Option<Self::Item> {
-		match self.almost_full_mask.next() {
-			None => None,
-			Some(mask) => {
-				let mov_black = Move::from_mask(mask, Black);
-				let mov_white = Move::from_mask(mask, White);
-				match (self.board.next(&mov_black), self.board.next(&mov_white)) {
-					(Some(b), None) => Some(b),
-					(None, Some(w)) => Some(w),
-					_ => self.next(),
+			MoveIterator::Forced(masks) => {
+				for mask in masks.by_ref() {
+					let mov_black = Move::from_mask(mask, Black);
+					let mov_white = Move::from_mask(mask, White);
+					let mov = match (
+						board.is_move_possible(&mov_black),
+						board.is_move_possible(&mov_white),
+					) {
+						(true, false) => mov_black,
+						(false, true) => mov_white,
+						_ => continue,
+					};
+					let undo = board.play(mov).expect("should play valid move");
+					return Some((mov, undo));
 				}
+				None
 			}
-		}
-	}
-}
-
-impl<'a> Iterator for ForcedMoveIterator<'a, (Board, Move)> {
-	type Item = (Board, Move);
-
-	fn next(&mut self) -> Option<Self::Item> {
-		match self.almost_full_mask.next() {
-			None => None,
-			Some(mask) => {
-				let mov_black = Move::from_mask(mask, Black);
-				let mov_white = Move::from_mask(mask, White);
-				match (self.board.next(&mov_black), self.board.next(&mov_white)) {
-					(Some(b), None) => Some((b, mov_black)),
-					(None, Some(w)) => Some((w, mov_white)),
-					_ => self.next(),
+			MoveIterator::All(moves) => {
+				for &mov in moves.by_ref() {
+					if let Ok(undo) = board.play(mov) {
+						return Some((mov, undo));
+					}
 				}
+				None
 			}
 		}
 	}
 }
 
-struct AllMoveIterator<'a, T> {
-	board: &'a Board,
-	index: usize,
-	len: usize,
-	heuristic: &'a [Move; 50],
-	return_type: std::marker::PhantomData<T>,
-}
-
-impl<'a> Iterator for AllMoveIterator<'a, Board> {
-	type Item = Board;
-
-	fn next(&mut self) -> Option<Self::Item> {
-		while self.index < self.len {
-			let mov = &self.heuristic[self.index];
-			self.index += 1;
-			if let Some(b) = self.board.next(mov) {
-				return Some(b);
-			}
-		}
-		None
-	}
-}
-
-impl<'a> Iterator for AllMoveIterator<'a, (Board, Move)> {
-	type Item = (Board, Move);
-
-	fn next(&mut self) -> Option<Self::Item> {
-		while self.index < self.len {
-			let mov = &self.heuristic[self.index];
-			self.index += 1;
-			if let Some(b) = self.board.next(mov) {
-				return Some((b, *mov));
-			}
-		}
-		None
-	}
-}
-
-// TODO(perf): Design a u64 key, and try partial key matching.
-// See https://www.chessprogramming.org/Transposition_Table
-fn key(board: &Board) -> u128 {
-	(board.pieces_mask as u128) | ((board.black_mask as u128) << 64)
-}
-
 // TODO: a smarter score computation could be done by taking into
 // account each player's score, and give a greater edge to a position
 // close to terminal. More interesting even is the idea of taking into
@@ -350,44 +305,119 @@ fn evaluation(board: &Board) -> EvaluationScore {
 	board.current_score() as EvaluationScore
 }
 
-pub fn solve(board: &Board, depth: Option<u8>) -> (EvaluationScore, Option<Move>, u128) {
-	let mut solver = Solver::new();
-
+/// Depth at which `board` is searched to the end of the game.
+fn max_depth(board: &Board) -> u8 {
 	let move_count = board.possible_moves().count() as u8;
 	// Adding FORCED_MOVE_DEPTH to the max depth to ensure we
 	// explore non-forcing moves up to the maximum if we can
 	// and only rely on forced moves if we cannot explore
 	// to full depth. Otherwise, we may end up not exploring
 	// some non-forced last moves.
-	let max_depth = (move_count + 1) / 2 + FORCED_MOVE_DEPTH;
-	let depth = depth.unwrap_or(max_depth as u8).min(max_depth as u8);
+	move_count.div_ceil(2) + FORCED_MOVE_DEPTH
+}
+
+pub fn solve(board: &Board, depth: Option<u8>) -> (EvaluationScore, Option<Move>, u128) {
+	let mut solver = Solver::new(None);
+
+	let max_depth = max_depth(board);
+	let depth = depth.unwrap_or(max_depth).min(max_depth);
 
-	let (score, mov) = solver.negamax0(board, MIN_SCORE, MAX_SCORE, depth);
+	let mut board = *board;
+	let (score, mov) = solver.negamax0(&mut board, MIN_SCORE, MAX_SCORE, depth);
 
 	(score, mov, solver.explored_positions)
 }
 
 pub fn partial_solve(board: &Board, depth: Option<u8>) -> (EvaluationScore, Option<Move>, u128) {
-	let mut solver = Solver::new();
+	let mut solver = Solver::new(None);
 
-	let move_count = board.possible_moves().count() as u8;
-	// Adding FORCED_MOVE_DEPTH to the max depth to ensure we
-	// explore non-forcing moves up to the maximum if we can
-	// and only rely on forced moves if we cannot explore
-	// to full depth. Otherwise, we may end up not exploring
-	// some non-forced last moves.
-	let max_depth = (move_count + 1) / 2 + FORCED_MOVE_DEPTH;
-	let depth = depth.unwrap_or(max_depth as u8).min(max_depth as u8);
+	let max_depth = max_depth(board);
+	let depth = depth.unwrap_or(max_depth).min(max_depth);
 
-	let (score, mov) = solver.negamax0(board, -1, 1, depth);
+	let mut board = *board;
+	let (score, mov) = solver.negamax0(&mut board, -1, 1, depth);
 
 	(score, mov, solver.explored_positions)
 }
 
+/**
+ * Like `solve`, but aborted as soon as `should_stop` returns true.
+ * It is given the number of positions explored so far, and called
+ * every thousand positions or so. The solution holds the principal
+ * variation of the search.
+ *
+ * `None` when the search was aborted or when there is no move. The
+ * depth of the solution is lower than `depth` when the board could
+ * be searched to the end with fewer moves.
+ */
+pub fn solve_until(
+	board: &Board,
+	depth: u8,
+	should_stop: &dyn Fn(u128) -> bool,
+) -> Option<TimedSolution> {
+	let mut solver = Solver::new(Some(should_stop));
+
+	let depth = depth.min(max_depth(board));
+
+	let mut board = *board;
+	let (score, mov) = solver.negamax0(&mut board, MIN_SCORE, MAX_SCORE, depth);
+
+	if solver.aborted {
+		return None;
+	}
+	Some(TimedSolution {
+		score,
+		mov: mov?,
+		explored_positions: solver.explored_positions,
+		depth,
+		pv: std::mem::take(&mut solver.pv[depth as usize]),
+	})
+}
+
+/// Result of `solve_within` or `solve_until`, a completed search.
+#[derive(Clone, Debug)]
+pub struct TimedSolution {
+	pub score: EvaluationScore,
+	pub mov: Move,
+	pub explored_positions: u128,
+	pub depth: u8,
+	/// Principal variation, `mov` followed by the replies the search
+	/// expects. It may stop short of `depth` when a transposition cut
+	/// the search.
+	pub pv: Vec<Move>,
+}
+
+/**
+ * Search with increasing depth, from 4 up to but excluding
+ * `max_depth`, and return the deepest search completed within
+ * `timeout`. `None` when even the first search did not complete or
+ * when there is no move.
+ *
+ * The search running when time is up is aborted.
+ */
+pub fn solve_within(board: &Board, max_depth: u8, timeout: Duration) -> Option<TimedSolution> {
+	let start = Instant::now();
+	let should_stop = |_| start.elapsed() >= timeout;
+
+	let mut best = None;
+	for depth in 4..max_depth {
+		let Some(solution) = solve_until(board, depth, &should_stop) else {
+			break;
+		};
+		// The game was already searched to its end.
+		let complete = solution.depth < depth;
+		best = Some(solution);
+		if complete {
+			break;
+		}
+	}
+	best
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use std::assert_matches::assert_matches;
+	use std::assert_matches;
 
 	#[test]
 	fn test_forced_moves() {
@@ -409,10 +439,13 @@ mod tests {
 			("bww/1w1ww/2wwb/1wbb/1b1ww 023679ab", vec!["bb3"]),
 			("bw2b/ww2b/bww1w/w1b/w1w1b 12346cdf", vec!["bb4"]),
 		] {
-			let board = Board::from_fen(fen).unwrap();
-			let forced: Vec<String> = next_boards::<(Board, Move)>(&board, true)
-				.map(|(_, m)| String::from(m))
-				.collect();
+			let mut board = Board::from_fen(fen).unwrap();
+			let mut moves = next_moves(&board, true);
+			let mut forced: Vec<String> = vec![];
+			while let Some((mov, undo)) = moves.play_next(&mut board) {
+				board.undo(undo);
+				forced.push(mov.into());
+			}
 			assert_eq!(forced, expected, "for board:\n{}", board.for_console());
 		}
 	}
@@ -443,6 +476,45 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn it_returns_the_principal_variation() {
+		let board = Board::from_fen("2bbw/bww1w/w1w1w/1w1bw/wbb1b 013679ce").unwrap();
+		let solution = solve_until(&board, 4, &|_| false).unwrap();
+		assert_eq!(solution.pv.first(), Some(&solution.mov));
+		assert!(solution.pv.len() <= 4);
+		let mut next = board;
+		for mov in &solution.pv {
+			next.play(*mov).unwrap();
+		}
+
+		// Endings are searched to the end, where the line leads to the
+		// expected score.
+		let board = Board::from_fen("wwwbb/bwbwb/bbbww/bbwww/w 01234567").unwrap();
+		let solution = solve_until(&board, 100, &|_| false).unwrap();
+		let mut next = board;
+		for mov in &solution.pv {
+			next.play(*mov).unwrap();
+		}
+		assert!(next.is_terminal());
+		let score = next.current_score() as EvaluationScore;
+		assert_eq!(
+			solution.score,
+			if solution.pv.len().is_multiple_of(2) {
+				score
+			} else {
+				-score
+			}
+		);
+	}
+
+	#[test]
+	fn it_solves_within_a_timeout() {
+		let board = Board::from_fen("2bbw/bww1w/w1w1w/1w1bw/wbb1b 013679ce").unwrap();
+		let solution = solve_within(&board, 6, Duration::from_secs(60)).unwrap();
+		assert_eq!(solution.depth, 5);
+		assert!(solve_within(&board, 4, Duration::from_secs(60)).is_none());
+	}
+
 	#[test]
 	#[ignore = "too slow, shall be used as a benchmark."]
 	fn depths() {
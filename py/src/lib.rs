@@ -1,8 +1,56 @@
 use ::ascacou as ascacou_rs;
 
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 
+create_exception!(
+	ascacou,
+	ParseError,
+	PyValueError,
+	"Raised when a FEN or a move cannot be parsed."
+);
+
+create_exception!(
+	ascacou,
+	InvalidBoard,
+	PyValueError,
+	"Raised when a FEN describes a position that cannot happen in a game."
+);
+
+fn parse_error(error: ascacou_rs::ParseError) -> PyErr {
+	ParseError::new_err(error.to_string())
+}
+
+/// `"first"` or `"second"`, as sides are named in Python.
+fn side_name(side: ascacou_rs::Side) -> &'static str {
+	match side {
+		ascacou_rs::Side::First => "first",
+		ascacou_rs::Side::Second => "second",
+	}
+}
+
+fn parse_side(name: &str) -> PyResult<ascacou_rs::Side> {
+	match name {
+		"first" => Ok(ascacou_rs::Side::First),
+		"second" => Ok(ascacou_rs::Side::Second),
+		_ => Err(PyValueError::new_err(format!(
+			"unknown side '{}', expected first or second",
+			name
+		))),
+	}
+}
+
+fn invalid_board(violations: Vec<ascacou_rs::Violation>) -> PyErr {
+	InvalidBoard::new_err(
+		violations
+			.iter()
+			.map(|violation| violation.to_string())
+			.collect::<Vec<String>>()
+			.join(", "),
+	)
+}
+
 #[pyclass]
 struct Board(ascacou_rs::Board);
 
@@ -10,10 +58,39 @@ struct Board(ascacou_rs::Board);
 impl Board {
 	#[new]
 	fn new(fen: &str) -> PyResult<Self> {
-		match ascacou_rs::Board::from_fen(fen) {
-			Ok(board) => Ok(Board(board)),
-			Err(s) => Err(PyRuntimeError::new_err(s)),
-		}
+		let board = ascacou_rs::Board::from_fen(fen).map_err(parse_error)?;
+		board.validate().map_err(invalid_board)?;
+		Ok(Board(board))
+	}
+
+	/// An empty board where the first player owns `tiles`, given
+	/// as 8 different numbers in the 0..16 range.
+	#[staticmethod]
+	fn from_tiles(tiles: Vec<u8>) -> PyResult<Self> {
+		let tiles = ascacou_rs::TileSet::try_from(tiles)
+			.map_err(|error| InvalidBoard::new_err(error.to_string()))?;
+		ascacou_rs::Board::empty_with_distribution(tiles)
+			.map(Board)
+			.map_err(|violation| invalid_board(vec![violation]))
+	}
+
+	/// An empty board with a random distribution, the same for a
+	/// given `seed`.
+	#[staticmethod]
+	fn random(seed: u64) -> Self {
+		Board(
+			ascacou_rs::Setup::random(seed)
+				.board()
+				.expect("random setups are complete"),
+		)
+	}
+
+	fn tiles(&self) -> Vec<u8> {
+		self.0.current_player.tiles().collect()
+	}
+
+	fn opponent_tiles(&self) -> Vec<u8> {
+		self.0.opponent().tiles().collect()
 	}
 
 	fn is_terminal(&self) -> bool {
@@ -25,25 +102,19 @@ impl Board {
 	}
 
 	fn possible_moves(&self) -> Vec<String> {
-		self.0
-			.possible_moves()
-			.into_iter()
-			.map(|mov| mov.into())
-			.collect()
+		self.0.possible_moves().map(|mov| mov.into()).collect()
 	}
 
 	fn next(&self, mov: String) -> PyResult<Self> {
 		match ascacou_rs::Move::try_from(mov) {
 			Ok(mov) => self
 				.0
-				.next(&mov)
-				.map(|board| Board(board))
-				.ok_or(PyRuntimeError::new_err(format!(
-					"Invalid move '{}' for board '{}'",
-					mov,
-					self.0.fen()
-				))),
-			Err(s) => Err(PyRuntimeError::new_err(s)),
+				.apply(&mov)
+				.map(|outcome| Board(outcome.board))
+				.map_err(|error| {
+					PyRuntimeError::new_err(format!("{} for board '{}'", error, self.0.fen()))
+				}),
+			Err(error) => Err(parse_error(error)),
 		}
 	}
 
@@ -51,6 +122,20 @@ impl Board {
 		self.0.current_score()
 	}
 
+	/// Draw the board as `"ansi"` (the default, as `str(board)`),
+	/// `"ascii"`, `"unicode"` or `"svg"`.
+	#[pyo3(signature = (format = "ansi"))]
+	fn render(&self, format: &str) -> PyResult<String> {
+		match ascacou_rs::renderer(format) {
+			Some(renderer) => Ok(renderer.board(&self.0)),
+			None => Err(PyValueError::new_err(format!(
+				"unknown format '{}', expected one of {}",
+				format,
+				ascacou_rs::RENDERER_NAMES.join(", ")
+			))),
+		}
+	}
+
 	fn __str__(&self) -> String {
 		self.0.for_console()
 	}
@@ -64,8 +149,153 @@ impl Board {
 	}
 }
 
+/// How tiles are distributed before the first move, see the `Setup`
+/// of the engine. Sides are named `"first"` and `"second"`.
+#[pyclass]
+struct Setup(ascacou_rs::Setup);
+
+#[pymethods]
+impl Setup {
+	/// The first player owns `tiles`, the second one owns the others.
+	#[staticmethod]
+	fn dealt(tiles: Vec<u8>) -> PyResult<Self> {
+		let tiles = ascacou_rs::TileSet::try_from(tiles)
+			.map_err(|error| InvalidBoard::new_err(error.to_string()))?;
+		ascacou_rs::Setup::dealt(tiles)
+			.map(Setup)
+			.map_err(|violation| invalid_board(vec![violation]))
+	}
+
+	/// A random distribution, the same for a given `seed`.
+	#[staticmethod]
+	fn random(seed: u64) -> Self {
+		Setup(ascacou_rs::Setup::random(seed))
+	}
+
+	/// A draft where players pick tiles in turn, the first player
+	/// starting.
+	#[staticmethod]
+	fn draft() -> Self {
+		Setup(ascacou_rs::Setup::draft())
+	}
+
+	/// Tiles no player owns yet.
+	fn available(&self) -> Vec<u8> {
+		self.0.available().collect()
+	}
+
+	fn tiles(&self, side: &str) -> PyResult<Vec<u8>> {
+		Ok(self.0.tiles(parse_side(side)?).collect())
+	}
+
+	/// The side expected to pick the next tile, `None` once complete.
+	fn to_pick(&self) -> Option<&'static str> {
+		self.0.to_pick().map(side_name)
+	}
+
+	/// Give `tile` to the side expected to pick, and return that side.
+	/// The last tile goes to the second player right away.
+	fn pick(&mut self, tile: u8) -> PyResult<&'static str> {
+		self.0
+			.pick(tile)
+			.map(side_name)
+			.map_err(|error| PyValueError::new_err(error.to_string()))
+	}
+
+	fn is_complete(&self) -> bool {
+		self.0.is_complete()
+	}
+
+	/// The empty board starting the game, `None` until complete.
+	fn board(&self) -> Option<Board> {
+		self.0.board().map(Board)
+	}
+
+	fn __repr__(&self) -> String {
+		format!(
+			"<Setup first={} second={}>",
+			self.0.tiles(ascacou_rs::Side::First),
+			self.0.tiles(ascacou_rs::Side::Second)
+		)
+	}
+}
+
+/// A game with its move history, see the `Game` of the engine.
+#[pyclass(unsendable)]
+struct Game(ascacou_rs::Game);
+
+#[pymethods]
+impl Game {
+	#[new]
+	#[pyo3(signature = (board = None))]
+	fn new(board: Option<&Board>) -> Self {
+		Game(ascacou_rs::Game::new(
+			board.map_or_else(ascacou_rs::Board::empty, |board| board.0),
+		))
+	}
+
+	#[getter]
+	fn board(&self) -> Board {
+		Board(*self.0.board())
+	}
+
+	fn moves(&self) -> Vec<String> {
+		self.0.moves().iter().map(|&mov| mov.into()).collect()
+	}
+
+	fn play(&mut self, mov: String) -> PyResult<()> {
+		let mov = ascacou_rs::Move::try_from(mov).map_err(parse_error)?;
+		self.0
+			.play(mov)
+			.map(|_| ())
+			.map_err(|error| PyRuntimeError::new_err(error.to_string()))
+	}
+
+	/// The move taken back, `None` at the start of the game.
+	fn undo(&mut self) -> Option<String> {
+		self.0.undo().map(String::from)
+	}
+
+	/// The move played again, `None` without undone moves.
+	fn redo(&mut self) -> Option<String> {
+		self.0.redo().map(|outcome| outcome.mov.into())
+	}
+
+	fn is_over(&self) -> bool {
+		self.0.is_over()
+	}
+
+	/// Score of the first player once the game is over, `None` before.
+	fn result(&self) -> Option<i8> {
+		self.0.result().map(|result| result.score)
+	}
+
+	/// Call `callback(event, move)` after each change, `event` being
+	/// `"played"`, `"undone"`, `"redone"` or `"ended"`. The move is
+	/// `None` for `"ended"`.
+	fn on_event(&mut self, callback: Py<PyAny>) {
+		self.0.on_event(move |event| {
+			let (name, mov): (&str, Option<String>) = match event {
+				ascacou_rs::GameEvent::Played(outcome) => ("played", Some(outcome.mov.into())),
+				ascacou_rs::GameEvent::Undone(mov) => ("undone", Some((*mov).into())),
+				ascacou_rs::GameEvent::Redone(outcome) => ("redone", Some(outcome.mov.into())),
+				ascacou_rs::GameEvent::Ended(_) => ("ended", None),
+			};
+			Python::attach(|py| {
+				if let Err(error) = callback.call1(py, (name, mov)) {
+					error.write_unraisable(py, None);
+				}
+			});
+		});
+	}
+
+	fn __str__(&self) -> String {
+		self.0.board().for_console()
+	}
+}
+
 #[pymodule]
 mod ascacou {
 	#[pymodule_export]
-	use super::Board;
+	use super::{Board, Game, InvalidBoard, ParseError, Setup};
 }
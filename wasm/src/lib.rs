@@ -1,4 +1,4 @@
-use ascacou::{Board, Move};
+use ascacou::{Board, Game, GameEvent, Move, ParseError, Setup, Side, TileSet};
 
 mod utils;
 
@@ -10,29 +10,56 @@ use wasm_bindgen::prelude::*;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+/// Turn an engine parse error into a JS `Error` named `ParseError`,
+/// its `offset` and `part` properties point at the faulty input.
+fn parse_error(error: ParseError) -> JsValue {
+	let js_error = js_sys::Error::new(&error.to_string());
+	js_error.set_name("ParseError");
+	let _ = js_sys::Reflect::set(&js_error, &"part".into(), &error.part().into());
+	let _ = js_sys::Reflect::set(&js_error, &"offset".into(), &error.offset().into());
+	js_error.into()
+}
+
+/// Parse a FEN and check that it describes a possible position,
+/// otherwise throw a JS `Error` named `InvalidBoard`.
+fn parse_board(fen: &str) -> Result<Board, JsValue> {
+	let board = Board::from_fen(fen).map_err(parse_error)?;
+	board.validate().map_err(|violations| {
+		let message = violations
+			.iter()
+			.map(|violation| violation.to_string())
+			.collect::<Vec<String>>()
+			.join(", ");
+		let js_error = js_sys::Error::new(&message);
+		js_error.set_name("InvalidBoard");
+		JsValue::from(js_error)
+	})?;
+	Ok(board)
+}
+
 #[wasm_bindgen]
-pub fn solve(fen: &str, depth: u8) -> Result<String, String> {
-	let board = Board::from_fen(fen)?;
+pub fn solve(fen: &str, depth: u8) -> Result<String, JsValue> {
+	let board = parse_board(fen)?;
 
 	match minicou::solve(&board, Some(depth)) {
 		(_, Some(mov), _) => Ok(mov.into()),
-		_ => Err("No solution found".to_string()),
+		_ => Err(js_sys::Error::new("No solution found").into()),
 	}
 }
 
 #[wasm_bindgen]
-pub fn play(fen: &str, #[wasm_bindgen(js_name = "move")] mov: &str) -> Result<String, String> {
-	let board = Board::from_fen(fen)?;
-	let mov = Move::try_from(mov.to_string())?;
-	match board.next(&mov) {
-		Some(next) => Ok(next.fen()),
-		None => Err("Invalid move".to_string()),
+pub fn play(fen: &str, #[wasm_bindgen(js_name = "move")] mov: &str) -> Result<String, JsValue> {
+	let board = parse_board(fen)?;
+	let mov = Move::try_from(mov).map_err(parse_error)?;
+	match board.apply(&mov) {
+		Ok(outcome) => Ok(outcome.board.fen()),
+		Err(error) => Err(js_sys::Error::new(&error.to_string()).into()),
 	}
 }
 
 #[wasm_bindgen]
-pub fn moves(fen: &str) -> Result<Vec<String>, String> {
-	let board = Board::from_fen(fen)?;
+pub fn moves(fen: &str) -> Result<Vec<String>, JsValue> {
+	let board = parse_board(fen)?;
 	let moves = board.possible_moves();
 	let mut rv = vec![];
 
@@ -43,3 +70,164 @@ pub fn moves(fen: &str) -> Result<Vec<String>, String> {
 
 	Ok(rv)
 }
+
+/// Tiles of the current player, as numbers in the 0..16 range.
+#[wasm_bindgen]
+pub fn tiles(fen: &str) -> Result<Vec<u8>, JsValue> {
+	let board = parse_board(fen)?;
+	Ok(board.current_player.tiles().collect())
+}
+
+/// Draw a board as `ansi`, `ascii`, `unicode` or `svg`.
+#[wasm_bindgen]
+pub fn render(fen: &str, format: &str) -> Result<String, JsValue> {
+	let board = parse_board(fen)?;
+	match ascacou::renderer(format) {
+		Some(renderer) => Ok(renderer.board(&board)),
+		None => Err(js_sys::Error::new(&format!("Unknown format '{}'", format)).into()),
+	}
+}
+
+/// `"first"` or `"second"`, as sides are named in JS.
+fn side_name(side: Side) -> String {
+	match side {
+		Side::First => "first".to_string(),
+		Side::Second => "second".to_string(),
+	}
+}
+
+/// How tiles are distributed before the first move, see the `Setup`
+/// of the engine.
+#[wasm_bindgen(js_name = "Setup")]
+pub struct JsSetup(Setup);
+
+#[wasm_bindgen(js_class = "Setup")]
+impl JsSetup {
+	/// The first player owns `tiles`, the second one owns the others.
+	pub fn dealt(tiles: Vec<u8>) -> Result<JsSetup, JsValue> {
+		let tiles = TileSet::try_from(tiles)
+			.map_err(|error| JsValue::from(js_sys::Error::new(&error.to_string())))?;
+		Setup::dealt(tiles)
+			.map(JsSetup)
+			.map_err(|violation| js_sys::Error::new(&violation.to_string()).into())
+	}
+
+	/// A random distribution, the same for a given `seed`.
+	pub fn random(seed: u32) -> JsSetup {
+		JsSetup(Setup::random(seed as u64))
+	}
+
+	/// A draft where players pick tiles in turn, the first player
+	/// starting.
+	pub fn draft() -> JsSetup {
+		JsSetup(Setup::draft())
+	}
+
+	/// Tiles no player owns yet.
+	pub fn available(&self) -> Vec<u8> {
+		self.0.available().collect()
+	}
+
+	/// Tiles of `side`, `first` or `second`.
+	pub fn tiles(&self, side: &str) -> Result<Vec<u8>, JsValue> {
+		let side = match side {
+			"first" => Side::First,
+			"second" => Side::Second,
+			_ => return Err(js_sys::Error::new(&format!("Unknown side '{}'", side)).into()),
+		};
+		Ok(self.0.tiles(side).collect())
+	}
+
+	/// The side expected to pick the next tile, if any.
+	#[wasm_bindgen(js_name = "toPick")]
+	pub fn to_pick(&self) -> Option<String> {
+		self.0.to_pick().map(side_name)
+	}
+
+	/// Give `tile` to the side expected to pick, and return that side.
+	/// The last tile goes to the second player right away.
+	pub fn pick(&mut self, tile: u8) -> Result<String, JsValue> {
+		self.0
+			.pick(tile)
+			.map(side_name)
+			.map_err(|error| js_sys::Error::new(&error.to_string()).into())
+	}
+
+	#[wasm_bindgen(js_name = "isComplete")]
+	pub fn is_complete(&self) -> bool {
+		self.0.is_complete()
+	}
+
+	/// FEN of the empty board starting the game, once complete.
+	pub fn board(&self) -> Option<String> {
+		self.0.board().map(|board| board.fen())
+	}
+}
+
+/// A game with its move history, see the `Game` of the engine.
+#[wasm_bindgen(js_name = "Game")]
+pub struct JsGame(Game);
+
+#[wasm_bindgen(js_class = "Game")]
+impl JsGame {
+	/// Start from `fen`, or from an empty board.
+	#[wasm_bindgen(constructor)]
+	pub fn new(fen: Option<String>) -> Result<JsGame, JsValue> {
+		let board = match fen {
+			Some(fen) => parse_board(&fen)?,
+			None => Board::empty(),
+		};
+		Ok(JsGame(Game::new(board)))
+	}
+
+	pub fn fen(&self) -> String {
+		self.0.board().fen()
+	}
+
+	pub fn moves(&self) -> Vec<String> {
+		self.0.moves().iter().map(|&mov| mov.into()).collect()
+	}
+
+	pub fn play(&mut self, #[wasm_bindgen(js_name = "move")] mov: &str) -> Result<(), JsValue> {
+		let mov = Move::try_from(mov).map_err(parse_error)?;
+		match self.0.play(mov) {
+			Ok(_) => Ok(()),
+			Err(error) => Err(js_sys::Error::new(&error.to_string()).into()),
+		}
+	}
+
+	/// The move taken back, if any.
+	pub fn undo(&mut self) -> Option<String> {
+		self.0.undo().map(String::from)
+	}
+
+	/// The move played again, if any.
+	pub fn redo(&mut self) -> Option<String> {
+		self.0.redo().map(|outcome| outcome.mov.into())
+	}
+
+	#[wasm_bindgen(js_name = "isOver")]
+	pub fn is_over(&self) -> bool {
+		self.0.is_over()
+	}
+
+	/// Score of the first player once the game is over.
+	pub fn result(&self) -> Option<i8> {
+		self.0.result().map(|result| result.score)
+	}
+
+	/// Call `callback(event, move)` after each change, `event` being
+	/// `played`, `undone`, `redone` or `ended`.
+	#[wasm_bindgen(js_name = "onEvent")]
+	pub fn on_event(&mut self, callback: js_sys::Function) {
+		self.0.on_event(move |event| {
+			let (name, mov) = match event {
+				GameEvent::Played(outcome) => ("played", JsValue::from(String::from(outcome.mov))),
+				GameEvent::Undone(mov) => ("undone", JsValue::from(String::from(*mov))),
+				GameEvent::Redone(outcome) => ("redone", JsValue::from(String::from(outcome.mov))),
+				GameEvent::Ended(_) => ("ended", JsValue::UNDEFINED),
+			};
+			let _ = callback.call2(&JsValue::NULL, &name.into(), &mov);
+		});
+	}
+}